@@ -0,0 +1,381 @@
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                           ARGUMENTS                                            //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+use clap::builder::PossibleValuesParser;
+use clap::Parser;
+use rtw_lib::camera::CameraSettings;
+use rtw_lib::environment::{self, DaylightSky, Environment, EnvironmentMap};
+use rtw_lib::integrator::{self, IntegratorKind};
+use rtw_lib::render::RenderSettings;
+use rtw_lib::scenes;
+use rtw_lib::vec::Vec3;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Render a scene with the Ray Tracing in One Weekend ray tracer.
+#[derive(Parser, Debug)]
+#[command(name = "rtw")]
+pub struct Args {
+    /// Which built-in scene to render.
+    #[arg(
+        default_value = "three_sphere_scene",
+        value_parser = PossibleValuesParser::new(scenes::NAMES)
+    )]
+    pub scene: String,
+
+    /// Render a JSON scene file instead of a built-in scene.  Render settings in the file are used
+    /// unless overridden by the options below.
+    #[arg(long, conflicts_with = "scene")]
+    pub scene_file: Option<PathBuf>,
+
+    /// Image width in pixels [default: 500]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(2..))]
+    pub width: Option<u32>,
+
+    /// Image height in pixels.  Defaults to the width divided by the aspect ratio.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(2..), conflicts_with = "aspect")]
+    pub height: Option<u32>,
+
+    /// Aspect ratio (width / height), given as a ratio like 16:9 or a number like 1.5 [default: 3:2]
+    #[arg(long, value_parser = parse_aspect)]
+    pub aspect: Option<f64>,
+
+    /// Samples per pixel [default: 10]
+    #[arg(long, value_parser = clap::value_parser!(i32).range(1..))]
+    pub spp: Option<i32>,
+
+    /// Maximum number of times a ray may bounce.  Most paths are ended sooner by Russian roulette
+    /// [default: 50]
+    #[arg(long, value_parser = clap::value_parser!(i32).range(1..))]
+    pub max_depth: Option<i32>,
+
+    /// Random seed.  Renders with the same seed and settings are identical.
+    #[arg(long, env = "RTW_SEED")]
+    pub seed: Option<u64>,
+
+    /// Background color as r,g,b, replacing the scene's environment.  Use 0,0,0 to light the scene
+    /// only with emissive materials.
+    #[arg(long, value_parser = parse_vec3)]
+    pub background: Option<Vec3<f64>>,
+
+    /// Light the scene with an equirectangular Radiance .hdr image, replacing its environment.
+    #[arg(long, conflicts_with = "background")]
+    pub environment_map: Option<PathBuf>,
+
+    /// Brightness multiplier for --environment-map.
+    #[arg(long, default_value_t = 1.0, value_parser = parse_non_negative)]
+    pub environment_intensity: f64,
+
+    /// Rotation of --environment-map about the vertical axis, in degrees.
+    #[arg(long, default_value_t = 0.0, value_parser = parse_f64, allow_hyphen_values = true)]
+    pub environment_rotation: f64,
+
+    /// Light the scene with a physically based daylight sky, with the sun this many degrees above
+    /// the horizon.  Replaces the scene's environment.
+    #[arg(
+        long,
+        conflicts_with_all = ["background", "environment_map"],
+        value_parser = parse_f64,
+        allow_hyphen_values = true
+    )]
+    pub sun_elevation: Option<f64>,
+
+    /// Direction of the sun for --sun-elevation, in degrees from -z toward +x.
+    #[arg(long, default_value_t = 0.0, value_parser = parse_f64, allow_hyphen_values = true)]
+    pub sun_azimuth: f64,
+
+    /// Haziness of the --sun-elevation sky, from about 2 (very clear) to 10 (hazy).
+    #[arg(long, default_value_t = environment::DEFAULT_TURBIDITY, value_parser = parse_positive)]
+    pub turbidity: f64,
+
+    /// How to color each sample: path (the real image), or for debugging normals, depth, albedo,
+    /// ambient_occlusion or bounce_heatmap.  Add a setting after a colon: the distance where depth
+    /// fades to black (depth:20), the occlusion radius (ambient_occlusion:0.5) or the ray count
+    /// shown as red (bounce_heatmap:16) [default: path]
+    #[arg(long, value_parser = parse_integrator)]
+    pub integrator: Option<IntegratorKind>,
+
+    /// Number of render threads.  Defaults to one per CPU.
+    #[arg(long, env = "RTW_THREADS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub threads: Option<u64>,
+
+    /// Where to write the image.  The format is chosen from the extension (png, jpg, ppm, ...).
+    /// Defaults to a timestamped PNG in the temp directory.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Camera position, as x,y,z.
+    #[arg(long, value_parser = parse_vec3, allow_hyphen_values = true)]
+    pub lookfrom: Option<Vec3<f64>>,
+
+    /// Point the camera looks at, as x,y,z.
+    #[arg(long, value_parser = parse_vec3, allow_hyphen_values = true)]
+    pub lookat: Option<Vec3<f64>>,
+
+    /// Camera "up" direction, as x,y,z.
+    #[arg(long, value_parser = parse_vec3, allow_hyphen_values = true)]
+    pub vup: Option<Vec3<f64>>,
+
+    /// Vertical field of view, in degrees.
+    #[arg(long, value_parser = parse_vfov)]
+    pub vfov: Option<f64>,
+
+    /// Lens aperture.  Zero gives a pinhole camera with everything in focus.
+    #[arg(long, value_parser = parse_non_negative)]
+    pub aperture: Option<f64>,
+
+    /// Distance from the camera to the plane of perfect focus.
+    #[arg(long, value_parser = parse_positive)]
+    pub focus_dist: Option<f64>,
+
+    /// Time the camera's shutter opens, from 0 to 1.  Objects that move while it's open are blurred.
+    #[arg(long, value_parser = parse_time)]
+    pub shutter_open: Option<f64>,
+
+    /// Time the camera's shutter closes, from 0 to 1.
+    #[arg(long, value_parser = parse_time)]
+    pub shutter_close: Option<f64>,
+}
+
+impl Args {
+    /// Apply any render setting overrides to `base`, which holds the defaults or the settings
+    /// from a scene file.  Without --height, giving --width or --aspect recomputes the height from
+    /// the width and aspect ratio.
+    pub fn render_settings(&self, base: RenderSettings) -> RenderSettings {
+        let width = self.width.unwrap_or(base.width);
+
+        let height = match (self.height, self.width, self.aspect) {
+            (Some(height), _, _) => height,
+            (None, None, None) => base.height,
+            (None, _, aspect) => {
+                let aspect = aspect.unwrap_or_else(|| base.aspect_ratio());
+                ((width as f64 / aspect).floor() as u32).max(2)
+            }
+        };
+
+        RenderSettings {
+            width,
+            height,
+            samples_per_pixel: self.spp.unwrap_or(base.samples_per_pixel),
+            max_depth: self.max_depth.unwrap_or(base.max_depth),
+            seed: self.seed.unwrap_or(base.seed),
+            threads: base.threads,
+            integrator: self.integrator.unwrap_or(base.integrator),
+        }
+    }
+
+    /// Apply the --background, --environment-map or --sun-elevation override to a scene's
+    /// environment.
+    pub fn environment(
+        &self,
+        scene_environment: Environment<f64>,
+    ) -> Result<Environment<f64>, String> {
+        if let Some(path) = &self.environment_map {
+            let map =
+                EnvironmentMap::load(path, self.environment_intensity, self.environment_rotation)
+                    .map_err(|err| format!("Error loading {}: {}", path.display(), err))?;
+
+            return Ok(Environment::Map(Arc::new(map)));
+        }
+
+        if let Some(elevation) = self.sun_elevation {
+            return Ok(Environment::Daylight(Arc::new(DaylightSky::new(
+                elevation,
+                self.sun_azimuth,
+                self.turbidity,
+                environment::DEFAULT_SUN_SIZE,
+                1.0,
+            ))));
+        }
+
+        Ok(match self.background {
+            Some(color) => Environment::Solid(color),
+            None => scene_environment,
+        })
+    }
+
+    /// Apply any camera overrides to a scene's camera settings.
+    pub fn camera(&self, scene_camera: CameraSettings<f64>) -> CameraSettings<f64> {
+        CameraSettings {
+            lookfrom: self.lookfrom.unwrap_or(scene_camera.lookfrom),
+            lookat: self.lookat.unwrap_or(scene_camera.lookat),
+            vup: self.vup.unwrap_or(scene_camera.vup),
+            vfov: self.vfov.unwrap_or(scene_camera.vfov),
+            aperture: self.aperture.unwrap_or(scene_camera.aperture),
+            focus_dist: self.focus_dist.unwrap_or(scene_camera.focus_dist),
+            shutter_open: self.shutter_open.unwrap_or(scene_camera.shutter_open),
+            shutter_close: self.shutter_close.unwrap_or(scene_camera.shutter_close),
+        }
+    }
+}
+
+fn parse_f64(s: &str) -> Result<f64, String> {
+    let n: f64 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", s))?;
+
+    if n.is_finite() {
+        Ok(n)
+    } else {
+        Err(format!("'{}' is not a finite number", s))
+    }
+}
+
+fn parse_positive(s: &str) -> Result<f64, String> {
+    match parse_f64(s)? {
+        n if n > 0.0 => Ok(n),
+        _ => Err("must be greater than zero".to_string()),
+    }
+}
+
+fn parse_non_negative(s: &str) -> Result<f64, String> {
+    match parse_f64(s)? {
+        n if n >= 0.0 => Ok(n),
+        _ => Err("must not be negative".to_string()),
+    }
+}
+
+fn parse_vfov(s: &str) -> Result<f64, String> {
+    match parse_f64(s)? {
+        n if n > 0.0 && n < 180.0 => Ok(n),
+        _ => Err("must be between 0 and 180 degrees".to_string()),
+    }
+}
+
+fn parse_time(s: &str) -> Result<f64, String> {
+    match parse_f64(s)? {
+        n if (0.0..=1.0).contains(&n) => Ok(n),
+        _ => Err("must be between 0 and 1".to_string()),
+    }
+}
+
+/// Parse an aspect ratio written as "16:9" or "1.5".
+fn parse_aspect(s: &str) -> Result<f64, String> {
+    let aspect = match s.split_once(':') {
+        Some((w, h)) => parse_positive(w)? / parse_positive(h)?,
+        None => parse_positive(s)?,
+    };
+
+    Ok(aspect)
+}
+
+/// Parse an integrator written as a name, optionally followed by a colon and its setting, like
+/// "ambient_occlusion:0.5".
+fn parse_integrator(s: &str) -> Result<IntegratorKind, String> {
+    let (name, setting) = match s.split_once(':') {
+        Some((name, setting)) => (name, Some(setting)),
+        None => (s, None),
+    };
+
+    let kind = IntegratorKind::by_name(name).ok_or_else(|| {
+        format!(
+            "'{}' is not an integrator; expected one of {}",
+            name,
+            integrator::NAMES.join(", ")
+        )
+    })?;
+
+    let setting = match setting {
+        Some(setting) => setting,
+        None => return Ok(kind),
+    };
+
+    match kind {
+        IntegratorKind::Depth { .. } => Ok(IntegratorKind::Depth {
+            max_distance: parse_positive(setting)?,
+        }),
+        IntegratorKind::AmbientOcclusion { .. } => Ok(IntegratorKind::AmbientOcclusion {
+            radius: parse_positive(setting)?,
+        }),
+        IntegratorKind::BounceHeatmap { .. } => Ok(IntegratorKind::BounceHeatmap {
+            max_rays: setting
+                .trim()
+                .parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!("'{}' is not a positive whole number", setting))?,
+        }),
+        _ => Err(format!("the {} integrator has no setting", name)),
+    }
+}
+
+/// Parse a vector written as "x,y,z".
+fn parse_vec3(s: &str) -> Result<Vec3<f64>, String> {
+    let parts = s
+        .split(',')
+        .map(parse_f64)
+        .collect::<Result<Vec<f64>, String>>()?;
+
+    match parts[..] {
+        [x, y, z] => Ok(Vec3 { x, y, z }),
+        _ => Err(format!(
+            "expected three numbers like 0,1.5,-2 but got '{}'",
+            s
+        )),
+    }
+}
+
+#[test]
+fn parse_args() {
+    let args = Args::try_parse_from([
+        "rtw",
+        "random_scene",
+        "--width",
+        "320",
+        "--aspect",
+        "16:9",
+        "--lookfrom",
+        "-1,2.5,3",
+    ])
+    .unwrap();
+
+    assert_eq!(args.scene, "random_scene");
+
+    let settings = args.render_settings(RenderSettings::default());
+    assert_eq!(settings.width, 320);
+    assert_eq!(settings.height, 180);
+    assert_eq!(settings.samples_per_pixel, 10);
+    assert_eq!(
+        args.lookfrom,
+        Some(Vec3 {
+            x: -1.0,
+            y: 2.5,
+            z: 3.0
+        })
+    );
+
+    let args = Args::try_parse_from(["rtw", "--scene-file", "scene.json", "--spp", "4"]).unwrap();
+    let base = RenderSettings {
+        width: 100,
+        height: 50,
+        samples_per_pixel: 64,
+        ..Default::default()
+    };
+    let settings = args.render_settings(base);
+    assert_eq!((settings.width, settings.height), (100, 50));
+    assert_eq!(settings.samples_per_pixel, 4);
+
+    assert!(Args::try_parse_from(["rtw", "no_such_scene"]).is_err());
+    assert!(Args::try_parse_from(["rtw", "random_scene", "--scene-file", "scene.json"]).is_err());
+    assert!(Args::try_parse_from(["rtw", "--spp", "0"]).is_err());
+    assert!(Args::try_parse_from(["rtw", "--lookat", "1,2"]).is_err());
+    assert!(Args::try_parse_from(["rtw", "--height", "200", "--aspect", "2"]).is_err());
+    assert!(Args::try_parse_from(["rtw", "--shutter-close", "1.5"]).is_err());
+
+    let args = Args::try_parse_from(["rtw", "--integrator", "ambient_occlusion:0.5"]).unwrap();
+    assert_eq!(
+        args.render_settings(RenderSettings::default()).integrator,
+        IntegratorKind::AmbientOcclusion { radius: 0.5 }
+    );
+    assert_eq!(
+        parse_integrator("depth"),
+        Ok(IntegratorKind::Depth {
+            max_distance: integrator::DEFAULT_MAX_DISTANCE
+        })
+    );
+    assert!(Args::try_parse_from(["rtw", "--integrator", "normals:2"]).is_err());
+    assert!(Args::try_parse_from(["rtw", "--integrator", "bounce_heatmap:0"]).is_err());
+    assert!(Args::try_parse_from(["rtw", "--integrator", "wireframe"]).is_err());
+}
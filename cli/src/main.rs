@@ -1,148 +1,95 @@
-use num::traits::Float;
+mod args;
+
+use clap::Parser;
 use pbr::ProgressBar;
-use rtw_lib::random::random_float;
 
-use rtw_lib::camera::Camera;
-use rtw_lib::hit::HitRecord;
-use rtw_lib::material::Lambertian;
+use rtw_lib::render::{RenderSettings, Renderer};
+use rtw_lib::scene_file::SceneFile;
 use rtw_lib::scenes as Scenes;
-use rtw_lib::vec::{Color, Point3, Vec3};
-use rtw_lib::write::{png, FinalImage};
+use rtw_lib::write::png;
 
-use std::rc::Rc;
+use args::Args;
+use std::process;
+use std::thread;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 //                                              MAIN                                              //
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-fn render() {
+fn render(args: Args) -> Result<(), String> {
     // Configuration
 
-    // let aspect_ratio = 3.0 / 2.0;
-    // let width = 400.0;
-    // let height = (width / aspect_ratio).floor();
-    // let samples_per_pixel: i32 = 100;
-    // let max_depth = 10;
-
-    let aspect_ratio = 3.0 / 2.0;
-    let width = 500.0;
-    let height = (width / aspect_ratio).floor();
+    let threads = match args.threads {
+        Some(threads) => threads as usize,
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
 
-    // Progress bar
-    let mut pb = ProgressBar::new((width * height) as u64);
+    let defaults = RenderSettings {
+        threads,
+        ..Default::default()
+    };
 
-    // let samples_per_pixel: i32 = 100;
-    // let max_depth = 25;
+    // World
 
-    let samples_per_pixel: i32 = 10;
-    let max_depth = 5;
+    let (scene, settings) = match &args.scene_file {
+        Some(path) => {
+            let file = SceneFile::load(path)
+                .map_err(|err| format!("Error loading {}: {}", path.display(), err))?;
+            let scene = file
+                .scene()
+                .map_err(|err| format!("Error loading {}: {}", path.display(), err))?;
 
-    // World
+            (scene, args.render_settings(file.render_settings(defaults)))
+        }
+        None => {
+            let scene = Scenes::by_name(&args.scene)
+                .ok_or_else(|| format!("There is no scene named '{}'.", args.scene))?;
 
-    // let world = Scenes::random_scene::scene();
-    // let world = Scenes::ten_spheres::scene();
-    let world = Scenes::three_sphere_scene::scene();
+            (scene, args.render_settings(defaults))
+        }
+    };
 
     // Camera
 
-    let lookfrom = Point3 {
-        x: 0.0,
-        y: 1.8,
-        z: 1.4,
-    };
-    let lookat = Point3 {
-        x: 0.0,
-        y: 0.0,
-        z: -3.0,
-    };
-    let vup = Vec3 {
-        x: 0.0,
-        y: 1.0,
-        z: 0.0,
-    };
-    let dist_to_focus = 10.0;
-    let aperture = 0.0;
-    let cam = Camera::new(
-        lookfrom,
-        lookat,
-        vup,
-        45.0,
-        aspect_ratio,
-        aperture,
-        dist_to_focus,
-    );
+    let cam = args.camera(scene.camera).build(settings.aspect_ratio());
 
-    // Render
+    // Environment
 
-    let mut pixels = vec![
-        Vec3 {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
-        };
-        (width * height) as usize
-    ];
-
-    // Default material to clone into each HitRecord
-    let default_material = Rc::new(Lambertian {
-        albedo: Color {
-            x: 122.0 / 255.0,
-            y: 175.0 / 255.0,
-            z: 238.0 / 255.0,
-        },
-    });
-
-    let mut total_rays: u64 = 0;
-
-    let mut i: usize = 0;
-    for y in (0..(height as i32)).rev() {
-        for x in 0..(width as i32) {
-            let p = &mut pixels[i];
-
-            for _ in 0..samples_per_pixel {
-                // don't use RNG if there's only one sample per pixel
-                let u_rand = if samples_per_pixel > 1 {
-                    random_float()
-                } else {
-                    1.0
-                };
-
-                let v_rand = if samples_per_pixel > 1 {
-                    random_float()
-                } else {
-                    1.0
-                };
-
-                let u = (u_rand + x as f64) / (width - 1.0);
-                let v = (v_rand + y as f64) / (height - 1.0);
-
-                let ray = cam.get_ray(u, v);
-
-                let mut rec = HitRecord::new(default_material.clone());
-
-                *p += ray.color(&mut rec, &world, max_depth);
-
-                total_rays += rec.ray_count;
-            }
-
-            pb.inc();
-            i += 1;
-        }
-    }
+    let environment = args.environment(scene.environment)?;
 
-    println!("Total rays: {}", total_rays);
+    // Progress bar
+    let mut pb = ProgressBar::new(settings.width as u64 * settings.height as u64);
+
+    // Render
 
-    png::write(FinalImage {
-        width: width as u32,
-        height: height as u32,
-        pixels,
-        samples_per_pixel,
-        total_rays,
-    });
+    let image = Renderer::new(&scene.world, &scene.lights, &environment, &cam, settings)
+        .render_with_progress(|| {
+            pb.add(settings.width as u64);
+        });
 
     pb.finish_print("Done!");
+    println!();
+    println!("Total rays: {}", image.total_rays);
+    println!(
+        "Paths ended by Russian roulette: {}",
+        image.terminated_paths
+    );
+
+    match &args.output {
+        Some(path) => {
+            png::write_to(&image, path)
+                .map_err(|err| format!("Error writing {}: {}", path.display(), err))?;
+            println!("Wrote {}", path.display());
+        }
+        None => png::write(image),
+    }
+
+    Ok(())
 }
 
 fn main() {
-    render();
+    if let Err(err) = render(Args::parse()) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,94 @@
+//! Compare the linear `HittableList` against the BVHs on `random_scene`.
+//!
+//!     cargo run --release --example bvh_stats
+
+use rtw_lib::bvh::{BvhNode, FlatBvh};
+use rtw_lib::camera::Camera;
+use rtw_lib::hit::{HitRecord, Hittable};
+use rtw_lib::material::Lambertian;
+use rtw_lib::random::{Rng, DEFAULT_SEED};
+use rtw_lib::scenes::random_scene;
+use rtw_lib::vec::{Color, Point3, Vec3};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const WIDTH: usize = 300;
+const HEIGHT: usize = 200;
+
+/// Cast one primary ray per pixel and return how long it took and how many rays hit something.
+fn time_primary_rays(world: &dyn Hittable<f64>, cam: &Camera<f64>) -> (Duration, usize) {
+    let material = Arc::new(Lambertian::new(Color::one()));
+    let mut rec = HitRecord::new(material);
+    let mut hits = 0;
+    let mut rng = Rng::new(DEFAULT_SEED);
+
+    let start = Instant::now();
+
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let u = x as f64 / (WIDTH - 1) as f64;
+            let v = y as f64 / (HEIGHT - 1) as f64;
+            let ray = cam.get_ray(u, v, &mut rng);
+
+            if world.hit(&ray, 0.001, f64::INFINITY, &mut rec, &mut rng) {
+                hits += 1;
+            }
+        }
+    }
+
+    (start.elapsed(), hits)
+}
+
+fn main() {
+    let cam = Camera::new(
+        Point3 {
+            x: 13.0,
+            y: 2.0,
+            z: 3.0,
+        },
+        Point3::zero(),
+        Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        20.0,
+        WIDTH as f64 / HEIGHT as f64,
+        0.0,
+        10.0,
+        0.0,
+        0.0,
+    );
+
+    let list = random_scene::objects();
+    let bvh_node = BvhNode::new(random_scene::objects());
+
+    let build_start = Instant::now();
+    let flat_bvh = FlatBvh::new(random_scene::objects());
+    let build_time = build_start.elapsed();
+
+    println!("SAH BVH built in {:?}", build_time);
+    println!("{}", flat_bvh.stats());
+    println!();
+
+    let (list_time, list_hits) = time_primary_rays(&list, &cam);
+    let (node_time, node_hits) = time_primary_rays(&bvh_node, &cam);
+    let (flat_time, flat_hits) = time_primary_rays(&flat_bvh, &cam);
+
+    let rays = WIDTH * HEIGHT;
+
+    println!("{} primary rays", rays);
+    println!("HittableList: {:?} ({} hits)", list_time, list_hits);
+    println!(
+        "BvhNode:      {:?} ({} hits, {:.1}x)",
+        node_time,
+        node_hits,
+        list_time.as_secs_f64() / node_time.as_secs_f64()
+    );
+    println!(
+        "FlatBvh:      {:?} ({} hits, {:.1}x)",
+        flat_time,
+        flat_hits,
+        list_time.as_secs_f64() / flat_time.as_secs_f64()
+    );
+}
@@ -0,0 +1,153 @@
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                    AXIS-ALIGNED BOUNDING BOX                                   //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+use crate::ray::Ray;
+use crate::vec::{Point3, Vec3};
+use num::Float;
+
+/// An axis-aligned bounding box, described by its minimum and maximum corners.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb<T: Float> {
+    pub min: Point3<T>,
+    pub max: Point3<T>,
+}
+
+impl<T: Float> Aabb<T> {
+    pub fn new(min: Point3<T>, max: Point3<T>) -> Aabb<T> {
+        Aabb { min, max }
+    }
+
+    /// Return true if the ray passes through the box anywhere in the interval [t_min, t_max].
+    pub fn hit(&self, r: &Ray<T>, t_min: T, t_max: T) -> bool {
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+
+        for axis in 0..3 {
+            let inv_d = T::one() / r.direction[axis];
+            let mut t0 = (self.min[axis] - r.origin[axis]) * inv_d;
+            let mut t1 = (self.max[axis] - r.origin[axis]) * inv_d;
+
+            if inv_d < T::zero() {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+
+            if t_max <= t_min {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Create the smallest box that contains both this box and another.
+    pub fn surrounding(&self, other: &Aabb<T>) -> Aabb<T> {
+        Aabb {
+            min: Vec3 {
+                x: self.min.x.min(other.min.x),
+                y: self.min.y.min(other.min.y),
+                z: self.min.z.min(other.min.z),
+            },
+            max: Vec3 {
+                x: self.max.x.max(other.max.x),
+                y: self.max.y.max(other.max.y),
+                z: self.max.z.max(other.max.z),
+            },
+        }
+    }
+
+    /// Get the total area of the box's six faces.
+    pub fn surface_area(&self) -> T {
+        let d = self.max - self.min;
+        (d.x * d.y + d.y * d.z + d.z * d.x) * T::from(2.0).unwrap()
+    }
+
+    /// Get the point in the middle of the box.
+    pub fn centroid(&self) -> Point3<T> {
+        (self.min + self.max) / T::from(2.0).unwrap()
+    }
+
+    /// Create a copy of the box that is at least `delta` thick along every axis.  Flat objects like
+    /// triangles have boxes with no thickness, which rays would never pass through.
+    pub fn pad(&self, delta: T) -> Aabb<T> {
+        let mut min = self.min;
+        let mut max = self.max;
+        let half = delta / T::from(2.0).unwrap();
+
+        for (lo, hi) in [
+            (&mut min.x, &mut max.x),
+            (&mut min.y, &mut max.y),
+            (&mut min.z, &mut max.z),
+        ] {
+            if *hi - *lo < delta {
+                *lo = *lo - half;
+                *hi = *hi + half;
+            }
+        }
+
+        Aabb { min, max }
+    }
+
+    /// Get the index (0 for x, 1 for y, 2 for z) of the axis along which the box is longest.
+    pub fn longest_axis(&self) -> usize {
+        let extent = self.max - self.min;
+
+        if extent.x > extent.y && extent.x > extent.z {
+            0
+        } else if extent.y > extent.z {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn aabb_hit_and_miss() {
+    let aabb = Aabb::new(
+        Point3 {
+            x: -1.0,
+            y: -1.0,
+            z: -1.0,
+        },
+        Point3 {
+            x: 1.0,
+            y: 1.0,
+            z: 1.0,
+        },
+    );
+
+    let toward = Ray {
+        origin: Point3 {
+            x: 0.0,
+            y: 0.0,
+            z: 5.0,
+        },
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time: 0.0,
+    };
+
+    let away = Ray {
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+        },
+        ..toward
+    };
+
+    assert!(aabb.hit(&toward, 0.001, f64::INFINITY));
+    assert!(!aabb.hit(&away, 0.001, f64::INFINITY));
+    assert!(!aabb.hit(&toward, 0.001, 3.0));
+}
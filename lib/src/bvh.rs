@@ -0,0 +1,8 @@
+/// A binary tree of bounding boxes that can be hit in logarithmic time.
+mod node;
+
+/// A surface area heuristic BVH stored as a flat array of nodes.
+mod flat;
+
+pub use flat::{BvhStats, FlatBvh};
+pub use node::BvhNode;
@@ -0,0 +1,573 @@
+////////////////
+//  FLAT BVH  //
+////////////////
+
+use crate::aabb::Aabb;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::Point3;
+use num::Float;
+use std::fmt;
+use std::fmt::Debug;
+
+/// Number of buckets centroids are sorted into when searching for the cheapest split.
+const BIN_COUNT: usize = 12;
+
+/// Nodes with this many objects or fewer become leaves if splitting them wouldn't be cheaper.
+const MAX_LEAF_SIZE: usize = 4;
+
+/// Estimated cost of testing a ray against a node's box, relative to hitting an object.
+const TRAVERSAL_COST: f64 = 1.0;
+
+/// Estimated cost of testing a ray against an object.
+const INTERSECTION_COST: f64 = 1.0;
+
+/// Below this depth, nodes are split at the object median instead of by SAH, which keeps the tree
+/// shallow enough for the fixed-size traversal stack.
+const MAX_SAH_DEPTH: usize = 32;
+
+/// Size of the traversal stack.  Median splits halve the object count, so no tree can get deeper
+/// than MAX_SAH_DEPTH plus log2 of the object count.
+const STACK_SIZE: usize = 64;
+
+/// A node in the flattened tree.  A node's first child always immediately follows it in the node
+/// array, so interior nodes only need to store where their second child is.
+#[derive(Debug, Copy, Clone)]
+struct FlatNode<T: Float> {
+    bbox: Aabb<T>,
+    /// For leaves, the index of the first object.  For interior nodes, the index of the second
+    /// child.
+    offset: u32,
+    /// Number of objects in a leaf, or zero for interior nodes.
+    count: u32,
+    /// The axis an interior node was split along.
+    axis: u8,
+}
+
+/// Statistics collected while building a FlatBvh.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BvhStats {
+    pub object_count: usize,
+    pub node_count: usize,
+    pub leaf_count: usize,
+    pub max_depth: usize,
+    pub min_leaf_size: usize,
+    pub max_leaf_size: usize,
+    /// Expected cost of hitting the tree with a random ray, in units of object intersections.
+    /// Testing every object in a plain list costs `object_count`.
+    pub sah_cost: f64,
+}
+
+impl BvhStats {
+    pub fn average_leaf_size(&self) -> f64 {
+        if self.leaf_count == 0 {
+            0.0
+        } else {
+            self.object_count as f64 / self.leaf_count as f64
+        }
+    }
+}
+
+impl fmt::Display for BvhStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "objects:     {}", self.object_count)?;
+        writeln!(
+            f,
+            "nodes:       {} ({} leaves)",
+            self.node_count, self.leaf_count
+        )?;
+        writeln!(f, "max depth:   {}", self.max_depth)?;
+        writeln!(
+            f,
+            "leaf size:   min {}, max {}, average {:.2}",
+            self.min_leaf_size,
+            self.max_leaf_size,
+            self.average_leaf_size()
+        )?;
+        write!(
+            f,
+            "SAH cost:    {:.2} (linear scan: {})",
+            self.sah_cost, self.object_count
+        )
+    }
+}
+
+/// A bounding volume hierarchy built with the binned surface area heuristic and stored as a flat
+/// array of nodes.  Rays walk the tree with an explicit stack instead of recursing through boxed
+/// children, and objects are reordered so every leaf's objects are adjacent in memory.
+///
+/// Like `BvhNode`, a `FlatBvh` is built from a `HittableList` and can stand in for it.
+pub struct FlatBvh<T: Float> {
+    nodes: Vec<FlatNode<T>>,
+    objects: Vec<Box<dyn Hittable<T>>>,
+    stats: BvhStats,
+}
+
+/// An object's bounds, cached while the tree is being built.
+struct BuildItem<T: Float> {
+    index: usize,
+    bbox: Aabb<T>,
+    centroid: Point3<T>,
+}
+
+impl<T: Float + Debug> FlatBvh<T> {
+    /// Build a BVH from every object in the list.
+    ///
+    /// Panics if any object has no bounding box.
+    pub fn new(list: HittableList<T>) -> FlatBvh<T> {
+        let objects = list.into_objects();
+
+        let mut items: Vec<BuildItem<T>> = objects
+            .iter()
+            .enumerate()
+            .map(|(index, obj)| {
+                let bbox = obj
+                    .bounding_box()
+                    .expect("Every object in a BVH must have a bounding box.");
+
+                BuildItem {
+                    index,
+                    bbox,
+                    centroid: bbox.centroid(),
+                }
+            })
+            .collect();
+
+        let mut builder = Builder {
+            nodes: Vec::with_capacity(2 * objects.len()),
+            order: Vec::with_capacity(objects.len()),
+            max_depth: 0,
+        };
+
+        if !items.is_empty() {
+            builder.build(&mut items, 0);
+        }
+
+        // Put the objects in leaf order.
+        let mut slots: Vec<Option<Box<dyn Hittable<T>>>> = objects.into_iter().map(Some).collect();
+        let objects = builder
+            .order
+            .iter()
+            .map(|&i| slots[i].take().unwrap())
+            .collect();
+
+        let stats = collect_stats(&builder.nodes, builder.max_depth);
+
+        FlatBvh {
+            nodes: builder.nodes,
+            objects,
+            stats,
+        }
+    }
+
+    /// Get statistics about the shape of the tree.
+    pub fn stats(&self) -> &BvhStats {
+        &self.stats
+    }
+}
+
+struct Builder<T: Float> {
+    nodes: Vec<FlatNode<T>>,
+    order: Vec<usize>,
+    max_depth: usize,
+}
+
+impl<T: Float> Builder<T> {
+    /// Recursively build the subtree for `items`, returning the index of its root node.
+    fn build(&mut self, items: &mut [BuildItem<T>], depth: usize) -> usize {
+        self.max_depth = self.max_depth.max(depth);
+
+        let node_index = self.nodes.len();
+
+        let bbox = items
+            .iter()
+            .skip(1)
+            .fold(items[0].bbox, |a, item| a.surrounding(&item.bbox));
+
+        let centroid_bounds = items.iter().skip(1).fold(
+            Aabb::new(items[0].centroid, items[0].centroid),
+            |a, item| a.surrounding(&Aabb::new(item.centroid, item.centroid)),
+        );
+
+        // Reserve this node's slot so its first child lands right after it.
+        self.nodes.push(FlatNode {
+            bbox,
+            offset: 0,
+            count: 0,
+            axis: 0,
+        });
+
+        let axis = centroid_bounds.longest_axis();
+        let extent = centroid_bounds.max[axis] - centroid_bounds.min[axis];
+
+        // Nothing can separate objects whose centers all coincide.
+        if items.len() == 1 || extent <= T::zero() {
+            return self.make_leaf(node_index, items);
+        }
+
+        let (mid, axis) = if depth < MAX_SAH_DEPTH {
+            match self.sah_split(items, &bbox, &centroid_bounds) {
+                Some(split) => split,
+                None => return self.make_leaf(node_index, items),
+            }
+        } else {
+            (median_split(items, axis), axis)
+        };
+
+        let (left, right) = items.split_at_mut(mid);
+
+        self.build(left, depth + 1);
+        let right_index = self.build(right, depth + 1);
+
+        self.nodes[node_index].offset = right_index as u32;
+        self.nodes[node_index].axis = axis as u8;
+
+        node_index
+    }
+
+    /// Find the cheapest binned split, partition `items` around it, and return the index of the
+    /// first item on the right side along with the split axis.  Returns None if the items are
+    /// cheaper to keep as a leaf.
+    fn sah_split(
+        &self,
+        items: &mut [BuildItem<T>],
+        bbox: &Aabb<T>,
+        centroid_bounds: &Aabb<T>,
+    ) -> Option<(usize, usize)> {
+        let mut best: Option<(f64, usize, usize)> = None; // (cost, axis, split bin)
+
+        let parent_area = bbox.surface_area().to_f64().unwrap();
+
+        for axis in 0..3 {
+            let min = centroid_bounds.min[axis];
+            let extent = centroid_bounds.max[axis] - min;
+
+            if extent <= T::zero() {
+                continue;
+            }
+
+            let mut counts = [0usize; BIN_COUNT];
+            let mut boxes: [Option<Aabb<T>>; BIN_COUNT] = [None; BIN_COUNT];
+
+            for item in items.iter() {
+                let b = bin_index(item.centroid[axis], min, extent);
+                counts[b] += 1;
+                boxes[b] = Some(match boxes[b] {
+                    Some(bin_box) => bin_box.surrounding(&item.bbox),
+                    None => item.bbox,
+                });
+            }
+
+            // Sweep from the right to get the area and count of everything right of each plane.
+            let mut right_area = [0.0; BIN_COUNT];
+            let mut right_count = [0usize; BIN_COUNT];
+            let mut acc_box: Option<Aabb<T>> = None;
+            let mut acc_count = 0;
+
+            for b in (1..BIN_COUNT).rev() {
+                acc_box = union(acc_box, boxes[b]);
+                acc_count += counts[b];
+                right_area[b] = acc_box.map_or(0.0, |a| a.surface_area().to_f64().unwrap());
+                right_count[b] = acc_count;
+            }
+
+            // Then sweep from the left, pricing the split in front of each bin.
+            let mut acc_box: Option<Aabb<T>> = None;
+            let mut acc_count = 0;
+
+            for split in 1..BIN_COUNT {
+                acc_box = union(acc_box, boxes[split - 1]);
+                acc_count += counts[split - 1];
+
+                if acc_count == 0 || right_count[split] == 0 {
+                    continue;
+                }
+
+                let left_area = acc_box.map_or(0.0, |a| a.surface_area().to_f64().unwrap());
+
+                let cost = TRAVERSAL_COST
+                    + INTERSECTION_COST
+                        * (left_area * acc_count as f64
+                            + right_area[split] * right_count[split] as f64)
+                        / parent_area;
+
+                let better = match best {
+                    Some((best_cost, _, _)) => cost < best_cost,
+                    None => true,
+                };
+
+                if better {
+                    best = Some((cost, axis, split));
+                }
+            }
+        }
+
+        let (cost, axis, split) = best?;
+
+        let leaf_cost = INTERSECTION_COST * items.len() as f64;
+
+        if items.len() <= MAX_LEAF_SIZE && leaf_cost <= cost {
+            return None;
+        }
+
+        let min = centroid_bounds.min[axis];
+        let extent = centroid_bounds.max[axis] - min;
+
+        let mid = partition(items, |item| {
+            bin_index(item.centroid[axis], min, extent) < split
+        });
+
+        Some((mid, axis))
+    }
+
+    fn make_leaf(&mut self, node_index: usize, items: &[BuildItem<T>]) -> usize {
+        let node = &mut self.nodes[node_index];
+        node.offset = self.order.len() as u32;
+        node.count = items.len() as u32;
+
+        self.order.extend(items.iter().map(|item| item.index));
+
+        node_index
+    }
+}
+
+/// Get the bin a centroid coordinate falls into.
+fn bin_index<T: Float>(c: T, min: T, extent: T) -> usize {
+    let b = ((c - min) / extent * T::from(BIN_COUNT).unwrap())
+        .to_usize()
+        .unwrap_or(0);
+    b.min(BIN_COUNT - 1)
+}
+
+fn union<T: Float>(a: Option<Aabb<T>>, b: Option<Aabb<T>>) -> Option<Aabb<T>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.surrounding(&b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// Split the items in half by centroid along the given axis, returning the midpoint.
+fn median_split<T: Float>(items: &mut [BuildItem<T>], axis: usize) -> usize {
+    let mid = items.len() / 2;
+
+    items.select_nth_unstable_by(mid, |a, b| {
+        a.centroid[axis]
+            .partial_cmp(&b.centroid[axis])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    mid
+}
+
+/// Move every item matching the predicate to the front, returning how many matched.
+fn partition<T: Float>(
+    items: &mut [BuildItem<T>],
+    predicate: impl Fn(&BuildItem<T>) -> bool,
+) -> usize {
+    let mut first_right = 0;
+
+    for i in 0..items.len() {
+        if predicate(&items[i]) {
+            items.swap(i, first_right);
+            first_right += 1;
+        }
+    }
+
+    first_right
+}
+
+/// Walk the finished tree to compute its statistics and SAH cost.
+fn collect_stats<T: Float>(nodes: &[FlatNode<T>], max_depth: usize) -> BvhStats {
+    let mut stats = BvhStats {
+        object_count: 0,
+        node_count: nodes.len(),
+        leaf_count: 0,
+        max_depth,
+        min_leaf_size: 0,
+        max_leaf_size: 0,
+        sah_cost: 0.0,
+    };
+
+    let root_area = match nodes.first() {
+        Some(root) => root.bbox.surface_area().to_f64().unwrap(),
+        None => return stats,
+    };
+
+    for node in nodes {
+        // A root with no area (a single point) is always hit, so every node is too.
+        let ratio = if root_area > 0.0 {
+            node.bbox.surface_area().to_f64().unwrap() / root_area
+        } else {
+            1.0
+        };
+
+        if node.count > 0 {
+            let count = node.count as usize;
+
+            stats.min_leaf_size = if stats.leaf_count == 0 {
+                count
+            } else {
+                stats.min_leaf_size.min(count)
+            };
+            stats.max_leaf_size = stats.max_leaf_size.max(count);
+            stats.leaf_count += 1;
+            stats.object_count += count;
+            stats.sah_cost += ratio * INTERSECTION_COST * count as f64;
+        } else {
+            stats.sah_cost += ratio * TRAVERSAL_COST;
+        }
+    }
+
+    stats
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for FlatBvh<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+
+        let mut hit_anything = false;
+        let mut closest_so_far = t_max;
+
+        let mut stack = [0usize; STACK_SIZE];
+        let mut stack_len = 0;
+        let mut current = 0;
+
+        loop {
+            let node = &self.nodes[current];
+
+            if node.bbox.hit(r, t_min, closest_so_far) {
+                if node.count > 0 {
+                    let first = node.offset as usize;
+                    let last = first + node.count as usize;
+
+                    for object in self.objects[first..last].iter() {
+                        if object.hit(r, t_min, closest_so_far, rec, rng) {
+                            hit_anything = true;
+                            closest_so_far = rec.t;
+                        }
+                    }
+                } else {
+                    // Visit the nearer child first so the farther one can be culled by
+                    // closest_so_far.
+                    let (near, far) = if r.direction[node.axis as usize] < T::zero() {
+                        (node.offset as usize, current + 1)
+                    } else {
+                        (current + 1, node.offset as usize)
+                    };
+
+                    stack[stack_len] = far;
+                    stack_len += 1;
+                    current = near;
+                    continue;
+                }
+            }
+
+            if stack_len == 0 {
+                break;
+            }
+
+            stack_len -= 1;
+            current = stack[stack_len];
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        self.nodes.first().map(|root| root.bbox)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+fn random_spheres(count: usize) -> HittableList<f64> {
+    use crate::material::Lambertian;
+    use crate::objects::sphere::Sphere;
+    use crate::random::{Rng, DEFAULT_SEED};
+    use crate::vec::Color;
+    use std::sync::Arc;
+
+    let material = Arc::new(Lambertian::new(Color::one()));
+
+    // A fixed seed keeps the scene identical between the list and the BVH.
+    let mut rng = Rng::new(DEFAULT_SEED);
+    let mut next = move || rng.random_float::<f64>();
+
+    let mut world = HittableList::new(Vec::new());
+
+    for _ in 0..count {
+        world.add(Box::new(Sphere {
+            center: Point3 {
+                x: next() * 20.0 - 10.0,
+                y: next() * 4.0,
+                z: next() * 20.0 - 10.0,
+            },
+            radius: 0.1 + next() * 0.4,
+            material: material.clone(),
+        }));
+    }
+
+    world
+}
+
+#[test]
+fn flat_bvh_matches_hittable_list() {
+    use crate::material::Lambertian;
+    use crate::vec::{Color, Vec3};
+    use std::sync::Arc;
+
+    let list = random_spheres(500);
+    let bvh = FlatBvh::new(random_spheres(500));
+
+    assert_eq!(list.bounding_box(), bvh.bounding_box());
+
+    let material = Arc::new(Lambertian::new(Color::one()));
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+
+    for i in 0..50 {
+        for j in 0..50 {
+            let ray = Ray {
+                origin: Point3 {
+                    x: 0.0,
+                    y: 6.0,
+                    z: 14.0,
+                },
+                direction: Vec3 {
+                    x: (i as f64 - 25.0) / 25.0,
+                    y: -0.3 + (j as f64 - 25.0) / 50.0,
+                    z: -1.0,
+                },
+                time: 0.0,
+            };
+
+            let mut list_rec = HitRecord::new(material.clone());
+            let mut bvh_rec = HitRecord::new(material.clone());
+
+            let list_hit = list.hit(&ray, 0.001, f64::INFINITY, &mut list_rec, &mut rng);
+            let bvh_hit = bvh.hit(&ray, 0.001, f64::INFINITY, &mut bvh_rec, &mut rng);
+
+            assert_eq!(list_hit, bvh_hit);
+            assert_eq!(list_rec.t, bvh_rec.t);
+            assert_eq!(list_rec.p, bvh_rec.p);
+        }
+    }
+}
+
+#[test]
+fn flat_bvh_stats() {
+    let bvh = FlatBvh::new(random_spheres(500));
+    let stats = bvh.stats();
+
+    assert_eq!(stats.object_count, 500);
+    assert_eq!(stats.node_count, 2 * stats.leaf_count - 1);
+    assert!(stats.min_leaf_size >= 1);
+    assert!(stats.max_depth < STACK_SIZE);
+    assert!(stats.sah_cost < 500.0);
+}
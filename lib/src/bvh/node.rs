@@ -0,0 +1,190 @@
+////////////////
+//  BVH NODE  //
+////////////////
+
+use crate::aabb::Aabb;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::random::Rng;
+use crate::ray::Ray;
+use num::Float;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+/// A node in a bounding volume hierarchy.  Each node holds the box surrounding everything beneath
+/// it, so a ray that misses the box can skip the whole subtree.
+///
+/// A `BvhNode` is built from a `HittableList` and is hit the same way, so a scene can switch to it
+/// by wrapping its list: `BvhNode::new(world)`.
+pub struct BvhNode<T: Float> {
+    left: Box<dyn Hittable<T>>,
+    right: Option<Box<dyn Hittable<T>>>,
+    bbox: Aabb<T>,
+}
+
+impl<T: Float + Debug + Send + Sync + 'static> BvhNode<T> {
+    /// Build a BVH from every object in the list.
+    ///
+    /// Panics if the list is empty or if any object has no bounding box.
+    pub fn new(list: HittableList<T>) -> BvhNode<T> {
+        BvhNode::from_objects(list.into_objects())
+    }
+
+    fn from_objects(mut objects: Vec<Box<dyn Hittable<T>>>) -> BvhNode<T> {
+        assert!(
+            !objects.is_empty(),
+            "Cannot build a BVH from an empty list."
+        );
+
+        let boxes: Vec<Aabb<T>> = objects
+            .iter()
+            .map(|obj| bounding_box_of(obj.as_ref()))
+            .collect();
+
+        // Split along the axis where the object centers are most spread out.
+        let centroid_bounds = boxes
+            .iter()
+            .map(|b| Aabb::new(b.centroid(), b.centroid()))
+            .reduce(|a, b| a.surrounding(&b))
+            .unwrap();
+        let axis = centroid_bounds.longest_axis();
+
+        let bbox = boxes.iter().skip(1).fold(boxes[0], |a, b| a.surrounding(b));
+
+        if objects.len() == 1 {
+            return BvhNode {
+                left: objects.pop().unwrap(),
+                right: None,
+                bbox,
+            };
+        }
+
+        objects.sort_by(|a, b| {
+            let a = bounding_box_of(a.as_ref()).centroid()[axis];
+            let b = bounding_box_of(b.as_ref()).centroid()[axis];
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        });
+
+        let right_objects = objects.split_off(objects.len() / 2);
+        let left_objects = objects;
+
+        BvhNode {
+            left: child(left_objects),
+            right: Some(child(right_objects)),
+            bbox,
+        }
+    }
+}
+
+/// Get an object's bounding box, which every object in a BVH must have.
+fn bounding_box_of<T: Float>(obj: &dyn Hittable<T>) -> Aabb<T> {
+    obj.bounding_box()
+        .expect("Every object in a BVH must have a bounding box.")
+}
+
+/// Turn a slice of the object list into a child node.  Single objects are stored directly rather
+/// than wrapped in a node of their own.
+fn child<T: Float + Debug + Send + Sync + 'static>(
+    mut objects: Vec<Box<dyn Hittable<T>>>,
+) -> Box<dyn Hittable<T>> {
+    if objects.len() == 1 {
+        objects.pop().unwrap()
+    } else {
+        Box::new(BvhNode::from_objects(objects))
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for BvhNode<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool {
+        if !self.bbox.hit(r, t_min, t_max) {
+            return false;
+        }
+
+        let hit_left = self.left.hit(r, t_min, t_max, rec, rng);
+
+        let hit_right = match &self.right {
+            Some(right) => right.hit(r, t_min, if hit_left { rec.t } else { t_max }, rec, rng),
+            None => false,
+        };
+
+        hit_left || hit_right
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        Some(self.bbox)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+fn sphere_grid() -> HittableList<f64> {
+    use crate::material::Lambertian;
+    use crate::objects::sphere::Sphere;
+    use crate::vec::{Color, Point3};
+    use std::sync::Arc;
+
+    let material = Arc::new(Lambertian::new(Color::one()));
+
+    let mut world = HittableList::new(Vec::new());
+
+    for a in -5..5 {
+        for b in -5..5 {
+            world.add(Box::new(Sphere {
+                center: Point3 {
+                    x: a as f64,
+                    y: 0.0,
+                    z: b as f64 * 1.3,
+                },
+                radius: 0.3 + 0.01 * ((a * b) as f64).abs(),
+                material: material.clone(),
+            }));
+        }
+    }
+
+    world
+}
+
+#[test]
+fn bvh_node_matches_hittable_list() {
+    use crate::material::Lambertian;
+    use crate::vec::{Color, Point3, Vec3};
+    use std::sync::Arc;
+
+    let list = sphere_grid();
+    let bvh = BvhNode::new(sphere_grid());
+
+    assert_eq!(list.bounding_box(), bvh.bounding_box());
+
+    let material = Arc::new(Lambertian::new(Color::one()));
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+
+    for i in 0..40 {
+        for j in 0..40 {
+            let ray = Ray {
+                origin: Point3 {
+                    x: 0.0,
+                    y: 4.0,
+                    z: 10.0,
+                },
+                direction: Vec3 {
+                    x: (i as f64 - 20.0) / 20.0,
+                    y: -0.4 + (j as f64 - 20.0) / 40.0,
+                    z: -1.0,
+                },
+                time: 0.0,
+            };
+
+            let mut list_rec = HitRecord::new(material.clone());
+            let mut bvh_rec = HitRecord::new(material.clone());
+
+            let list_hit = list.hit(&ray, 0.001, f64::INFINITY, &mut list_rec, &mut rng);
+            let bvh_hit = bvh.hit(&ray, 0.001, f64::INFINITY, &mut bvh_rec, &mut rng);
+
+            assert_eq!(list_hit, bvh_hit);
+            assert_eq!(list_rec.t, bvh_rec.t);
+            assert_eq!(list_rec.p, bvh_rec.p);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use num::traits::Float;
 use std::fmt::Debug;
 
+use crate::random::Rng;
 use crate::ray::Ray;
 use crate::vec::{Point3, Vec3};
 
@@ -19,10 +20,15 @@ pub struct Camera<T: Float + Debug> {
     v: Vec3<T>,
     w: Vec3<T>,
     lens_radius: T,
+    /// Times at which the shutter opens and closes.
+    time0: T,
+    time1: T,
 }
 
 impl<T: Float + Debug> Camera<T> {
-    /// Create a new Camera.
+    /// Create a new Camera.  Each ray is cast at a random time between the shutter opening and
+    /// closing, which blurs objects that move in that interval.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         lookfrom: Point3<T>,
         lookat: Point3<T>,
@@ -31,6 +37,8 @@ impl<T: Float + Debug> Camera<T> {
         aspect_ratio: T,
         aperture: T,
         focus_dist: T,
+        shutter_open: T,
+        shutter_close: T,
     ) -> Camera<T> {
         let two = T::from(2.0).unwrap();
 
@@ -60,19 +68,62 @@ impl<T: Float + Debug> Camera<T> {
             v,
             w,
             lens_radius,
+            time0: shutter_open,
+            time1: shutter_close,
         }
     }
 
     /// Get a ray at (u,v).
-    pub fn get_ray(&self, s: T, t: T) -> Ray<T> {
-        let rd = Vec3::<T>::random_in_unit_disk() * self.lens_radius;
+    pub fn get_ray(&self, s: T, t: T, rng: &mut Rng) -> Ray<T> {
+        let rd = Vec3::<T>::random_in_unit_disk(rng) * self.lens_radius;
         let offset = self.u * rd.x + self.v * rd.y;
 
+        // Leave the random sequence alone when nothing can move, so still renders don't change.
+        let time = if self.time0 == self.time1 {
+            self.time0
+        } else {
+            rng.random_float_in_range(self.time0, self.time1)
+        };
+
         Ray {
             origin: self.origin + offset,
             direction: self.lower_left_corner + self.horizontal * s + self.vertical * t
                 - self.origin
                 - offset,
+            time,
         }
     }
 }
+
+/// The parameters that place a camera in a scene.  Scenes provide these instead of a finished
+/// Camera so they can be adjusted, and so the aspect ratio can come from the render settings.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CameraSettings<T: Float + Debug> {
+    pub lookfrom: Point3<T>,
+    pub lookat: Point3<T>,
+    pub vup: Vec3<T>,
+    /// Vertical field of view, in degrees.
+    pub vfov: T,
+    pub aperture: T,
+    pub focus_dist: T,
+    /// Shutter open and close times, within [0, 1].  Equal times freeze any motion.
+    pub shutter_open: T,
+    pub shutter_close: T,
+}
+
+impl<T: Float + Debug> CameraSettings<T> {
+    /// Create a Camera with these settings.
+    pub fn build(&self, aspect_ratio: T) -> Camera<T> {
+        Camera::new(
+            self.lookfrom,
+            self.lookat,
+            self.vup,
+            self.vfov,
+            aspect_ratio,
+            self.aperture,
+            self.focus_dist,
+            self.shutter_open,
+            self.shutter_close,
+        )
+    }
+}
@@ -0,0 +1,130 @@
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                           ENVIRONMENT                                          //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+mod daylight;
+mod distribution;
+mod map;
+
+pub use daylight::{DaylightSky, DEFAULT_SUN_SIZE, DEFAULT_TURBIDITY};
+pub use map::EnvironmentMap;
+
+use crate::random::Rng;
+use crate::vec::{Color, Vec3};
+use num::Float;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// What rays see when they leave the scene without hitting anything.  The environment is also a
+/// light source, unless it's `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Environment<T: Float> {
+    /// Black, so emissive materials are the only light.
+    None,
+    /// The same color in every direction.
+    Solid(Color<T>),
+    /// A blend from `bottom` straight down to `top` straight up.
+    Gradient { bottom: Color<T>, top: Color<T> },
+    /// An HDR image surrounding the scene.
+    Map(Arc<EnvironmentMap<T>>),
+    /// A physically based clear sky with a sun.
+    Daylight(Arc<DaylightSky<T>>),
+}
+
+impl<T: Float + Debug> Environment<T> {
+    /// The soft blue sky used by the built-in scenes.
+    pub fn sky() -> Environment<T> {
+        Environment::Gradient {
+            bottom: Vec3 {
+                x: T::from(248.0 / 255.0).unwrap(),
+                y: T::from(245.0 / 255.0).unwrap(),
+                z: T::from(254.0 / 255.0).unwrap(),
+            },
+            top: Vec3 {
+                x: T::from(139.0 / 255.0).unwrap(),
+                y: T::from(179.0 / 255.0).unwrap(),
+                z: T::from(237.0 / 255.0).unwrap(),
+            },
+        }
+    }
+
+    /// Get the light arriving from a direction.  The direction doesn't need to be a unit vector.
+    pub fn color(&self, direction: &Vec3<T>) -> Color<T> {
+        match self {
+            Environment::None => Color::zero(),
+            Environment::Solid(color) => *color,
+            Environment::Gradient { bottom, top } => {
+                let unit_direction = direction.unit();
+                let t = T::from(0.5).unwrap() * (unit_direction.y + T::one());
+
+                *bottom * (T::one() - t) + *top * t
+            }
+            Environment::Map(map) => map.color(direction),
+            Environment::Daylight(sky) => sky.color(direction),
+        }
+    }
+
+    /// Whether the environment has bright spots that bounce rays should be aimed at with `sample`.
+    pub fn is_sampled(&self) -> bool {
+        matches!(self, Environment::Map(_) | Environment::Daylight(_))
+    }
+
+    /// Pick a unit direction, favoring the brightest parts of the environment.
+    pub fn sample(&self, rng: &mut Rng) -> Vec3<T> {
+        match self {
+            Environment::Map(map) => map.sample(rng),
+            Environment::Daylight(sky) => sky.sample(rng),
+            _ => Vec3::random_unit_vector(rng),
+        }
+    }
+
+    /// Get the probability density, per unit solid angle, of `sample` picking a direction.
+    pub fn pdf(&self, direction: &Vec3<T>) -> T {
+        match self {
+            Environment::Map(map) => map.pdf(direction),
+            Environment::Daylight(sky) => sky.pdf(direction),
+            _ => T::one() / T::from(4.0 * std::f64::consts::PI).unwrap(),
+        }
+    }
+}
+
+impl<T: Float + Debug> Default for Environment<T> {
+    fn default() -> Self {
+        Environment::sky()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn environment_colors() {
+    let up = Vec3 {
+        x: 0.0,
+        y: 2.0,
+        z: 0.0,
+    };
+    let down = up * -1.0;
+
+    let red = Color {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    let blue = Color {
+        x: 0.0,
+        y: 0.0,
+        z: 1.0,
+    };
+
+    let gradient = Environment::Gradient {
+        bottom: red,
+        top: blue,
+    };
+
+    assert_eq!(gradient.color(&up), blue);
+    assert_eq!(gradient.color(&down), red);
+    assert_eq!(Environment::Solid(red).color(&up), red);
+    assert_eq!(Environment::None.color(&down), Color::zero());
+}
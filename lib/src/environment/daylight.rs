@@ -0,0 +1,292 @@
+////////////////////
+//  DAYLIGHT SKY  //
+////////////////////
+
+use crate::random::Rng;
+use crate::vec::{Color, Vec3};
+use num::Float;
+use std::f64::consts::PI;
+use std::fmt::Debug;
+
+/// Converts the model's luminance, in kcd/m², to scene units.  Chosen so that a white diffuse
+/// surface under a clear midday sky and sun comes out close to white.
+const SKY_SCALE: f64 = 0.03;
+
+/// Turbidity of a typical clear day.
+pub const DEFAULT_TURBIDITY: f64 = 3.0;
+
+/// Angular radius of the sun disk in degrees.  A little larger than the real sun, which softens
+/// shadow edges without looking out of place.
+pub const DEFAULT_SUN_SIZE: f64 = 0.5;
+
+/// Irradiance from an overhead sun before the atmosphere dims it, in scene units.
+const SUN_IRRADIANCE: f64 = 2.2;
+
+/// An analytic clear sky using the Preetham model, with a sun disk.  Directions below the horizon
+/// see a dimmed copy of the horizon, standing in for the ground.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaylightSky<T: Float> {
+    /// Unit vector pointing at the sun.
+    sun_direction: Vec3<T>,
+    /// Cosine of the sun disk's angular radius.
+    sun_cos_radius: T,
+    /// Light coming from each point on the sun disk.
+    sun_radiance: Color<T>,
+    /// Angle between the zenith and the sun, limited to the horizon.
+    theta_sun: f64,
+    /// Sky luminance and chromaticity straight up.
+    zenith: [f64; 3],
+    /// Perez coefficients A to E for luminance and the two chromaticity coordinates.
+    perez: [[f64; 5]; 3],
+    intensity: f64,
+}
+
+impl<T: Float + Debug> DaylightSky<T> {
+    /// Create a sky.  Elevation is the sun's angle above the horizon and azimuth its angle from -z
+    /// toward +x, both in degrees.  Turbidity describes haze, from about 2 (very clear) to 10
+    /// (hazy).  The sun's size is its angular radius in degrees, which is about 0.27 for the real
+    /// sun; larger suns give softer shadows.
+    pub fn new(
+        elevation: f64,
+        azimuth: f64,
+        turbidity: f64,
+        sun_size: f64,
+        intensity: f64,
+    ) -> DaylightSky<T> {
+        let (elevation, azimuth) = (elevation.to_radians(), azimuth.to_radians());
+        let sun_direction = Vec3 {
+            x: elevation.cos() * azimuth.sin(),
+            y: elevation.sin(),
+            z: -elevation.cos() * azimuth.cos(),
+        };
+
+        // The model isn't meant for a sun below the horizon.
+        let theta_sun = (PI / 2.0 - elevation).min(PI / 2.0 - 0.01);
+        let t = turbidity;
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_sun);
+        let zenith_luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+
+        let theta = [theta_sun.powi(3), theta_sun.powi(2), theta_sun, 1.0];
+        let chromaticity = |m: [[f64; 4]; 3]| {
+            let row = |r: [f64; 4]| r.iter().zip(theta.iter()).map(|(a, b)| a * b).sum::<f64>();
+            t * t * row(m[0]) + t * row(m[1]) + row(m[2])
+        };
+
+        let zenith_x = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let zenith_y = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+
+        let perez = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        // Spread the sun's irradiance over its disk, dimmed by the air it passes through.
+        let sun_radius = sun_size.max(0.01).to_radians();
+        let solid_angle = 2.0 * PI * (1.0 - sun_radius.cos());
+        let transmittance = sun_transmittance(elevation, turbidity);
+        let sun_radiance = transmittance * (SUN_IRRADIANCE * intensity / solid_angle);
+
+        DaylightSky {
+            sun_direction: to_t(sun_direction),
+            sun_cos_radius: T::from(sun_radius.cos()).unwrap(),
+            sun_radiance: to_t(sun_radiance),
+            theta_sun,
+            zenith: [zenith_luminance, zenith_x, zenith_y],
+            perez,
+            intensity,
+        }
+    }
+
+    /// Get the light arriving from a direction, which doesn't need to be a unit vector.
+    pub fn color(&self, direction: &Vec3<T>) -> Color<T> {
+        let d = direction.unit();
+
+        if d.y > T::zero() && d.dot(&self.sun_direction) >= self.sun_cos_radius {
+            return self.sun_radiance + self.sky(&d);
+        }
+
+        self.sky(&d)
+    }
+
+    /// Pick a unit direction within the sun disk.
+    pub fn sample(&self, rng: &mut Rng) -> Vec3<T> {
+        // Uniformly distributed over the cone of directions toward the sun.
+        let cos_theta = T::one() - rng.random_float::<T>() * (T::one() - self.sun_cos_radius);
+        let sin_theta = (T::one() - cos_theta * cos_theta).max(T::zero()).sqrt();
+        let phi = T::from(2.0 * PI).unwrap() * rng.random_float();
+
+        self.sun_direction.from_local(&Vec3 {
+            x: phi.cos() * sin_theta,
+            y: phi.sin() * sin_theta,
+            z: cos_theta,
+        })
+    }
+
+    /// Get the probability density, per unit solid angle, of `sample` picking a direction.
+    pub fn pdf(&self, direction: &Vec3<T>) -> T {
+        if direction.unit().dot(&self.sun_direction) >= self.sun_cos_radius {
+            T::one() / (T::from(2.0 * PI).unwrap() * (T::one() - self.sun_cos_radius))
+        } else {
+            T::zero()
+        }
+    }
+
+    /// Get the sky's light from a unit direction, without the sun.
+    fn sky(&self, d: &Vec3<T>) -> Color<T> {
+        let y = d.y.to_f64().unwrap();
+
+        // Below the horizon, use the horizon dimmed to stand in for the ground.
+        let (cos_theta, ground) = if y > 0.001 { (y, 1.0) } else { (0.001, 0.3) };
+
+        let sun = [
+            self.sun_direction.x.to_f64().unwrap(),
+            self.sun_direction.y.to_f64().unwrap(),
+            self.sun_direction.z.to_f64().unwrap(),
+        ];
+        let flat = (1.0 - cos_theta * cos_theta).sqrt();
+        let horizontal = (d.x.to_f64().unwrap().powi(2) + d.z.to_f64().unwrap().powi(2)).sqrt();
+        let (dx, dz) = if horizontal > 0.0 {
+            (
+                d.x.to_f64().unwrap() / horizontal * flat,
+                d.z.to_f64().unwrap() / horizontal * flat,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        let cos_gamma = (dx * sun[0] + cos_theta * sun[1] + dz * sun[2]).clamp(-1.0, 1.0);
+        let gamma = cos_gamma.acos();
+
+        let value = |i: usize| {
+            let p = self.perez[i];
+            let f = |cos_theta: f64, gamma: f64| {
+                (1.0 + p[0] * (p[1] / cos_theta).exp())
+                    * (1.0 + p[2] * (p[3] * gamma).exp() + p[4] * gamma.cos().powi(2))
+            };
+
+            self.zenith[i] * f(cos_theta, gamma) / f(1.0, self.theta_sun)
+        };
+
+        let luminance = value(0).max(0.0) * SKY_SCALE * self.intensity * ground;
+        let (x, y) = (value(1), value(2));
+
+        to_t(xyy_to_rgb(x, y, luminance))
+    }
+}
+
+/// Estimate how much of each color channel of sunlight makes it through the atmosphere, using
+/// Rayleigh scattering for red, green and blue light plus haze that grows with turbidity.
+fn sun_transmittance(elevation: f64, turbidity: f64) -> Vec3<f64> {
+    if elevation <= 0.0 {
+        return Vec3::zero();
+    }
+
+    // Kasten and Young's relative air mass, which stays finite at the horizon.
+    let zenith_degrees = 90.0 - elevation.to_degrees();
+    let air_mass = 1.0 / (elevation.sin() + 0.50572 * (96.07995 - zenith_degrees).powf(-1.6364));
+
+    let rayleigh = Vec3 {
+        x: 0.043,
+        y: 0.1,
+        z: 0.242,
+    };
+    let haze = 0.02 * turbidity;
+
+    Vec3 {
+        x: (-(rayleigh.x + haze) * air_mass).exp(),
+        y: (-(rayleigh.y + haze) * air_mass).exp(),
+        z: (-(rayleigh.z + haze) * air_mass).exp(),
+    }
+}
+
+/// Convert a CIE xyY color to linear sRGB.
+fn xyy_to_rgb(x: f64, y: f64, luminance: f64) -> Vec3<f64> {
+    if y <= 0.0 {
+        return Vec3::zero();
+    }
+
+    let big_x = x / y * luminance;
+    let big_z = (1.0 - x - y) / y * luminance;
+
+    Vec3 {
+        x: (3.2406 * big_x - 1.5372 * luminance - 0.4986 * big_z).max(0.0),
+        y: (-0.9689 * big_x + 1.8758 * luminance + 0.0415 * big_z).max(0.0),
+        z: (0.0557 * big_x - 0.2040 * luminance + 1.0570 * big_z).max(0.0),
+    }
+}
+
+fn to_t<T: Float>(v: Vec3<f64>) -> Vec3<T> {
+    Vec3 {
+        x: T::from(v.x).unwrap(),
+        y: T::from(v.y).unwrap(),
+        z: T::from(v.z).unwrap(),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn daylight_sky_colors() {
+    use crate::random::DEFAULT_SEED;
+
+    let noon: DaylightSky<f64> = DaylightSky::new(60.0, 0.0, 3.0, 0.5, 1.0);
+    let sunset: DaylightSky<f64> = DaylightSky::new(2.0, 0.0, 3.0, 0.5, 1.0);
+
+    let up = Vec3 {
+        x: 0.0,
+        y: 1.0,
+        z: 0.0,
+    };
+
+    // A clear sky is blue overhead, and darker when the sun is low.
+    let zenith = noon.color(&up);
+    assert!(zenith.z > zenith.x);
+    assert!(sunset.color(&up).y < zenith.y);
+
+    // Sunlight is much brighter than the sky, and redder at sunset.
+    let noon_sun = noon.color(&noon.sun_direction);
+    let sunset_sun = sunset.color(&sunset.sun_direction);
+    assert!(noon_sun.y > 100.0 * zenith.y);
+    assert!(sunset_sun.x / sunset_sun.z > noon_sun.x / noon_sun.z);
+
+    // Every sampled direction is within the sun disk.
+    let mut rng = Rng::new(DEFAULT_SEED);
+
+    for _ in 0..100 {
+        let direction = noon.sample(&mut rng);
+        assert!(noon.pdf(&direction) > 0.0);
+        assert!(noon.color(&direction).y > noon.sun_radiance.y);
+    }
+
+    assert_eq!(noon.pdf(&up), 0.0);
+}
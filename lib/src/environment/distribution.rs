@@ -0,0 +1,144 @@
+////////////////////////////////////////
+//  PIECEWISE-CONSTANT DISTRIBUTIONS  //
+////////////////////////////////////////
+
+/// A distribution over [0, 1) proportional to a list of non-negative weights, each covering an
+/// equal slice of the interval.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Distribution1D {
+    func: Vec<f64>,
+    cdf: Vec<f64>,
+    integral: f64,
+}
+
+impl Distribution1D {
+    pub(crate) fn new(func: Vec<f64>) -> Distribution1D {
+        let n = func.len();
+        let mut cdf = vec![0.0; n + 1];
+
+        for i in 0..n {
+            cdf[i + 1] = cdf[i] + func[i] / n as f64;
+        }
+
+        let integral = cdf[n];
+
+        // With nothing to go on, fall back to a uniform distribution.
+        for (i, c) in cdf.iter_mut().enumerate() {
+            *c = if integral > 0.0 {
+                *c / integral
+            } else {
+                i as f64 / n as f64
+            };
+        }
+
+        Distribution1D {
+            func,
+            cdf,
+            integral,
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.func.len()
+    }
+
+    /// Turn a uniform random number into a sample.  Returns the sample, its density and the index
+    /// of the slice it fell in.
+    pub(crate) fn sample(&self, u: f64) -> (f64, f64, usize) {
+        // The last slice whose cdf is at or below u.
+        let offset = self
+            .cdf
+            .partition_point(|&c| c <= u)
+            .saturating_sub(1)
+            .min(self.count() - 1);
+
+        let width = self.cdf[offset + 1] - self.cdf[offset];
+        let du = if width > 0.0 {
+            (u - self.cdf[offset]) / width
+        } else {
+            0.0
+        };
+
+        let x = (offset as f64 + du) / self.count() as f64;
+
+        (x, self.pdf(offset), offset)
+    }
+
+    /// Get the density of the distribution within a slice.
+    pub(crate) fn pdf(&self, index: usize) -> f64 {
+        if self.integral > 0.0 {
+            self.func[index] / self.integral
+        } else {
+            1.0
+        }
+    }
+}
+
+/// A distribution over the unit square, made of a distribution over rows and one over the
+/// columns of each row.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Distribution2D {
+    rows: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    /// Build a distribution from weights stored row by row.
+    pub(crate) fn new(func: &[f64], width: usize, height: usize) -> Distribution2D {
+        let rows: Vec<Distribution1D> = func
+            .chunks(width)
+            .take(height)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+
+        let marginal = Distribution1D::new(rows.iter().map(|row| row.integral).collect());
+
+        Distribution2D { rows, marginal }
+    }
+
+    /// Turn two uniform random numbers into a point (u, v) in the unit square and its density.
+    pub(crate) fn sample(&self, u1: f64, u2: f64) -> ((f64, f64), f64) {
+        let (v, pdf_v, row) = self.marginal.sample(u2);
+        let (u, pdf_u, _) = self.rows[row].sample(u1);
+
+        ((u, v), pdf_u * pdf_v)
+    }
+
+    /// Get the density at a point in the unit square.
+    pub(crate) fn pdf(&self, u: f64, v: f64) -> f64 {
+        let row = ((v * self.rows.len() as f64) as usize).min(self.rows.len() - 1);
+        let column = ((u * self.rows[row].count() as f64) as usize).min(self.rows[row].count() - 1);
+
+        self.rows[row].pdf(column) * self.marginal.pdf(row)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn distribution_samples_follow_weights() {
+    let dist = Distribution2D::new(&[0.0, 1.0, 0.0, 3.0], 2, 2);
+
+    // The left column has no weight, so every sample lands on the right.
+    let mut bottom = 0;
+
+    for i in 0..1000 {
+        let u1 = (i % 37) as f64 / 37.0;
+        let u2 = i as f64 / 1000.0;
+        let ((u, v), pdf) = dist.sample(u1, u2);
+
+        assert!((0.5..1.0).contains(&u));
+        assert_eq!(pdf, dist.pdf(u, v));
+
+        if v >= 0.5 {
+            bottom += 1;
+        }
+    }
+
+    // Three quarters of the weight is in the bottom row.
+    assert_eq!(bottom, 750);
+    assert_eq!(dist.pdf(0.75, 0.75), 3.0);
+    assert_eq!(dist.pdf(0.25, 0.75), 0.0);
+}
@@ -0,0 +1,287 @@
+///////////////////////
+//  ENVIRONMENT MAP  //
+///////////////////////
+
+use super::distribution::Distribution2D;
+use crate::random::Rng;
+use crate::vec::{Color, Vec3};
+use image::codecs::hdr::HdrDecoder;
+use image::{ImageError, ImageResult};
+use num::Float;
+use std::f64::consts::PI;
+use std::fmt;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// An equirectangular image of everything surrounding the scene.  The top row of the image is
+/// straight up, the bottom row straight down, and the middle column looks down -z.
+///
+/// The map also keeps a distribution over its pixels, weighted by brightness, so bounce rays can
+/// be aimed at the bright parts of the image.
+#[derive(Clone, PartialEq)]
+pub struct EnvironmentMap<T: Float> {
+    width: usize,
+    height: usize,
+    /// Row by row, already scaled by the intensity.
+    pixels: Vec<Color<T>>,
+    /// Rotation about the y axis, in radians.
+    rotation: T,
+    distribution: Distribution2D,
+}
+
+impl<T: Float + Debug> EnvironmentMap<T> {
+    /// Create a map from pixels stored row by row, top to bottom.  `intensity` scales every pixel
+    /// and `rotation` turns the map about the y axis, in degrees.
+    ///
+    /// Panics if there isn't exactly one pixel for each position in the image.
+    pub fn new(
+        width: usize,
+        height: usize,
+        pixels: Vec<Color<T>>,
+        intensity: T,
+        rotation: T,
+    ) -> EnvironmentMap<T> {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "An environment map needs width * height pixels."
+        );
+        assert!(
+            width > 0 && height > 0,
+            "An environment map can't be empty."
+        );
+
+        let pixels: Vec<Color<T>> = pixels.into_iter().map(|p| p * intensity).collect();
+
+        // Rows near the poles cover less of the sphere than rows near the horizon, so they're
+        // weighted by sin(theta).
+        let weights: Vec<f64> = pixels
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let theta = PI * ((i / width) as f64 + 0.5) / height as f64;
+                luminance(p) * theta.sin()
+            })
+            .collect();
+
+        EnvironmentMap {
+            width,
+            height,
+            pixels,
+            rotation: rotation.to_radians(),
+            distribution: Distribution2D::new(&weights, width, height),
+        }
+    }
+
+    /// Load a Radiance `.hdr` image.  OpenEXR isn't supported.
+    pub fn load(path: &Path, intensity: T, rotation: T) -> ImageResult<EnvironmentMap<T>> {
+        let decoder = HdrDecoder::new(BufReader::new(
+            File::open(path).map_err(ImageError::IoError)?,
+        ))?;
+        let metadata = decoder.metadata();
+
+        let pixels = decoder
+            .read_image_hdr()?
+            .into_iter()
+            .map(|p| Color {
+                x: T::from(p[0]).unwrap(),
+                y: T::from(p[1]).unwrap(),
+                z: T::from(p[2]).unwrap(),
+            })
+            .collect();
+
+        Ok(EnvironmentMap::new(
+            metadata.width as usize,
+            metadata.height as usize,
+            pixels,
+            intensity,
+            rotation,
+        ))
+    }
+
+    /// Get the light arriving from a direction, which doesn't need to be a unit vector.
+    pub fn color(&self, direction: &Vec3<T>) -> Color<T> {
+        let (u, v) = self.direction_to_uv(direction);
+
+        let x = ((u * self.width as f64) as usize).min(self.width - 1);
+        let y = ((v * self.height as f64) as usize).min(self.height - 1);
+
+        self.pixels[y * self.width + x]
+    }
+
+    /// Pick a unit direction, favoring bright parts of the map.
+    pub fn sample(&self, rng: &mut Rng) -> Vec3<T> {
+        let ((u, v), _) = self
+            .distribution
+            .sample(rng.random_float(), rng.random_float());
+
+        self.uv_to_direction(u, v)
+    }
+
+    /// Get the probability density, per unit solid angle, of `sample` picking a direction.
+    pub fn pdf(&self, direction: &Vec3<T>) -> T {
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta = (PI * v).sin();
+
+        if sin_theta <= 0.0 {
+            return T::zero();
+        }
+
+        // The unit square maps onto the sphere with area 2π × π, squeezed by sin(theta).
+        T::from(self.distribution.pdf(u, v) / (2.0 * PI * PI * sin_theta)).unwrap()
+    }
+
+    fn direction_to_uv(&self, direction: &Vec3<T>) -> (f64, f64) {
+        let d = rotate_y(&direction.unit(), -self.rotation);
+
+        let x = d.x.to_f64().unwrap();
+        let y = d.y.to_f64().unwrap();
+        let z = d.z.to_f64().unwrap();
+
+        let u = 0.5 + x.atan2(-z) / (2.0 * PI);
+        let v = y.clamp(-1.0, 1.0).acos() / PI;
+
+        (u, v)
+    }
+
+    fn uv_to_direction(&self, u: f64, v: f64) -> Vec3<T> {
+        let phi = 2.0 * PI * (u - 0.5);
+        let theta = PI * v;
+
+        let d = Vec3 {
+            x: T::from(theta.sin() * phi.sin()).unwrap(),
+            y: T::from(theta.cos()).unwrap(),
+            z: T::from(-theta.sin() * phi.cos()).unwrap(),
+        };
+
+        rotate_y(&d, self.rotation)
+    }
+}
+
+impl<T: Float> Debug for EnvironmentMap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnvironmentMap")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+fn luminance<T: Float>(c: &Color<T>) -> f64 {
+    (c.x.to_f64().unwrap() * 0.2126
+        + c.y.to_f64().unwrap() * 0.7152
+        + c.z.to_f64().unwrap() * 0.0722)
+        .max(0.0)
+}
+
+fn rotate_y<T: Float>(v: &Vec3<T>, angle: T) -> Vec3<T> {
+    let (sin, cos) = angle.sin_cos();
+
+    Vec3 {
+        x: v.x * cos + v.z * sin,
+        y: v.y,
+        z: v.z * cos - v.x * sin,
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn environment_map_sampling() {
+    use crate::random::DEFAULT_SEED;
+
+    // Dim everywhere, with one bright pixel, and turned so sampling has to undo the rotation.
+    let (width, height) = (16, 8);
+    let mut pixels = vec![
+        Color {
+            x: 0.1,
+            y: 0.1,
+            z: 0.1
+        };
+        width * height
+    ];
+    pixels[2 * width + 5] = Color {
+        x: 500.0,
+        y: 400.0,
+        z: 300.0,
+    };
+
+    let map = EnvironmentMap::new(width, height, pixels, 2.0, 30.0);
+
+    // The pdf integrates to one over the sphere.
+    let (columns, rows) = (512, 256);
+    let d_phi = 2.0 * PI / columns as f64;
+    let d_theta = PI / rows as f64;
+    let mut integral = 0.0;
+
+    for row in 0..rows {
+        let theta = (row as f64 + 0.5) * d_theta;
+
+        for column in 0..columns {
+            let phi = (column as f64 + 0.5) * d_phi;
+            let direction = Vec3 {
+                x: theta.sin() * phi.cos(),
+                y: theta.cos(),
+                z: theta.sin() * phi.sin(),
+            };
+
+            integral += map.pdf(&direction) * theta.sin() * d_theta * d_phi;
+        }
+    }
+
+    assert!(
+        (integral - 1.0).abs() < 0.01,
+        "pdf integrates to {}",
+        integral
+    );
+
+    // Most samples land on the bright pixel.
+    let mut rng = Rng::new(DEFAULT_SEED);
+    let bright = (0..1000)
+        .filter(|_| map.color(&map.sample(&mut rng)).x == 1000.0)
+        .count();
+
+    assert!(bright > 900, "only {} samples hit the bright pixel", bright);
+}
+
+#[test]
+fn environment_map_loads_hdr() {
+    use image::codecs::hdr::HdrEncoder;
+    use image::Rgb;
+
+    let (width, height) = (4, 2);
+    let pixels: Vec<Rgb<f32>> = (0..width * height)
+        .map(|i| Rgb([i as f32, 0.5, 2.0]))
+        .collect();
+
+    let path = std::env::temp_dir().join(format!("rtw-test-{}.hdr", std::process::id()));
+    HdrEncoder::new(File::create(&path).unwrap())
+        .encode(&pixels, width, height)
+        .unwrap();
+
+    let map: EnvironmentMap<f64> = EnvironmentMap::load(&path, 1.0, 0.0).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!((map.width, map.height), (width, height));
+
+    // Straight up is in the top row and looking down -z is in the middle column.
+    let ahead = Vec3 {
+        x: 0.0,
+        y: 0.1,
+        z: -1.0,
+    };
+    assert_eq!(
+        map.color(&ahead),
+        Color {
+            x: 2.0,
+            y: 0.5,
+            z: 2.0
+        }
+    );
+
+    assert!(EnvironmentMap::<f64>::load(Path::new("/no/such/sky.hdr"), 1.0, 0.0).is_err());
+}
@@ -2,31 +2,41 @@
 //                                            HITTABLES                                           //
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+use crate::aabb::Aabb;
 use crate::material::Material;
+use crate::random::Rng;
 use crate::ray::Ray;
 use crate::vec::{Point3, Vec3};
 use num::Float;
 use std::fmt::Debug;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub struct HitRecord<T: Float> {
     pub p: Point3<T>,
     pub normal: Vec3<T>,
-    pub material: Rc<dyn Material<T>>,
+    pub material: Arc<dyn Material<T>>,
     pub t: T,
+    /// Surface coordinates of the hit point, for texturing.
+    pub u: T,
+    pub v: T,
     pub front_face: bool,
     pub ray_count: u64,
+    /// Paths ended early by Russian roulette.
+    pub terminated_paths: u64,
 }
 
 impl<T: Float> HitRecord<T> {
-    pub fn new(material: Rc<dyn Material<T>>) -> HitRecord<T> {
+    pub fn new(material: Arc<dyn Material<T>>) -> HitRecord<T> {
         HitRecord {
             p: Point3::zero(),
             normal: Vec3::zero(),
             t: T::zero(),
+            u: T::zero(),
+            v: T::zero(),
             front_face: false,
             material,
             ray_count: 0,
+            terminated_paths: 0,
         }
     }
 
@@ -40,8 +50,43 @@ impl<T: Float> HitRecord<T> {
     }
 }
 
-pub trait Hittable<T: Float> {
-    fn hit(&self, ray: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>) -> bool;
+pub trait Hittable<T: Float>: Send + Sync {
+    fn hit(&self, ray: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool;
+
+    /// Get the box enclosing this object, or None if it is unbounded.
+    fn bounding_box(&self) -> Option<Aabb<T>>;
+
+    /// Get the probability density, per unit solid angle, of `random` picking `direction` from
+    /// `origin`.  Objects that don't know how to aim at themselves pick any direction evenly, which
+    /// is noisy as a light but still unbiased.
+    fn pdf_value(&self, _origin: &Point3<T>, _direction: &Vec3<T>) -> T {
+        T::one() / T::from(4.0 * std::f64::consts::PI).unwrap()
+    }
+
+    /// Pick a direction from `origin` toward a random point on the object.  The direction isn't
+    /// necessarily a unit vector.  Must agree with `pdf_value`.
+    fn random(&self, _origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        Vec3::random_unit_vector(rng)
+    }
+}
+
+/// Shared objects, like a model placed several times by `Instance`, can go anywhere a hittable can.
+impl<T: Float, H: Hittable<T> + ?Sized> Hittable<T> for Arc<H> {
+    fn hit(&self, ray: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool {
+        (**self).hit(ray, t_min, t_max, rec, rng)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        (**self).bounding_box()
+    }
+
+    fn pdf_value(&self, origin: &Point3<T>, direction: &Vec3<T>) -> T {
+        (**self).pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        (**self).random(origin, rng)
+    }
 }
 
 /////////////////////
@@ -67,13 +112,27 @@ impl<T: Float + Debug> HittableList<T> {
         self.objects.push(obj);
     }
 
-    pub fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>) -> bool {
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Take ownership of the objects in this list.
+    pub fn into_objects(self) -> Vec<Box<dyn Hittable<T>>> {
+        self.objects
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for HittableList<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool {
         let mut hit_anything = false;
         let mut closest_so_far = t_max;
-        let mut rec = rec;
 
         for object in self.objects.iter() {
-            if object.hit(r, t_min, closest_so_far, &mut rec) {
+            if object.hit(r, t_min, closest_so_far, rec, rng) {
                 hit_anything = true;
                 closest_so_far = rec.t;
             }
@@ -81,4 +140,34 @@ impl<T: Float + Debug> HittableList<T> {
 
         hit_anything
     }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        let mut objects = self.objects.iter();
+        let mut bbox = objects.next()?.bounding_box()?;
+
+        for object in objects {
+            bbox = bbox.surrounding(&object.bounding_box()?);
+        }
+
+        Some(bbox)
+    }
+
+    /// Sampling a list picks one of its objects at random, so the density is their average.
+    fn pdf_value(&self, origin: &Point3<T>, direction: &Vec3<T>) -> T {
+        if self.objects.is_empty() {
+            return T::zero();
+        }
+
+        let total = self.objects.iter().fold(T::zero(), |sum, object| {
+            sum + object.pdf_value(origin, direction)
+        });
+
+        total / T::from(self.objects.len()).unwrap()
+    }
+
+    fn random(&self, origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        let index = (rng.random_float::<f64>() * self.objects.len() as f64) as usize;
+
+        self.objects[index.min(self.objects.len() - 1)].random(origin, rng)
+    }
 }
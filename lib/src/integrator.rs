@@ -0,0 +1,247 @@
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                           INTEGRATORS                                          //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+mod albedo;
+mod ambient_occlusion;
+mod bounce_heatmap;
+mod depth;
+mod normals;
+mod path;
+
+pub use albedo::Albedo;
+pub use ambient_occlusion::AmbientOcclusion;
+pub use bounce_heatmap::BounceHeatmap;
+pub use depth::Depth;
+pub use normals::Normals;
+pub use path::PathTracer;
+
+use crate::environment::Environment;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::Color;
+use num::Float;
+use std::fmt::Debug;
+
+/// Works out the color of a pixel sample from a camera ray.  The path tracer gives the real image;
+/// the others show one thing about the first surface each ray hits, for finding out what's wrong
+/// with a scene.
+pub trait Integrator<T: Float>: Send + Sync {
+    /// Get the color for a camera ray.  Rays cast are counted in `rec`.
+    fn radiance(
+        &self,
+        ray: &Ray<T>,
+        world: &dyn Hittable<T>,
+        lights: &HittableList<T>,
+        environment: &Environment<T>,
+        rec: &mut HitRecord<T>,
+        rng: &mut Rng,
+    ) -> Color<T>;
+}
+
+/// Which integrator to render with, as a render setting.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum IntegratorKind {
+    /// The full image.
+    #[default]
+    Path,
+    /// Surface normals, with x, y and z as red, green and blue.
+    Normals,
+    /// Distance to the first hit, white up close fading to black at `max_distance`.
+    Depth { max_distance: f64 },
+    /// The color of the first surface hit, without any lighting.
+    Albedo,
+    /// How much of the sky each surface sees past anything within `radius`.
+    AmbientOcclusion { radius: f64 },
+    /// Rays cast per sample, from blue for few to red for `max_rays` or more.
+    BounceHeatmap { max_rays: u32 },
+}
+
+/// The names of the integrators, as used by the CLI and scene files.
+pub const NAMES: [&str; 6] = [
+    "path",
+    "normals",
+    "depth",
+    "albedo",
+    "ambient_occlusion",
+    "bounce_heatmap",
+];
+
+pub const DEFAULT_MAX_DISTANCE: f64 = 10.0;
+pub const DEFAULT_AO_RADIUS: f64 = 1.0;
+pub const DEFAULT_MAX_RAYS: u32 = 32;
+
+impl IntegratorKind {
+    /// Get an integrator by name, with its default settings.
+    pub fn by_name(name: &str) -> Option<IntegratorKind> {
+        Some(match name {
+            "path" => IntegratorKind::Path,
+            "normals" => IntegratorKind::Normals,
+            "depth" => IntegratorKind::Depth {
+                max_distance: DEFAULT_MAX_DISTANCE,
+            },
+            "albedo" => IntegratorKind::Albedo,
+            "ambient_occlusion" => IntegratorKind::AmbientOcclusion {
+                radius: DEFAULT_AO_RADIUS,
+            },
+            "bounce_heatmap" => IntegratorKind::BounceHeatmap {
+                max_rays: DEFAULT_MAX_RAYS,
+            },
+            _ => return None,
+        })
+    }
+
+    /// Make the integrator.  `max_depth` is the most bounces the path tracer follows.
+    pub fn build<T: Float + Debug + Send + Sync + 'static>(
+        &self,
+        max_depth: i32,
+    ) -> Box<dyn Integrator<T>> {
+        match *self {
+            IntegratorKind::Path => Box::new(PathTracer { max_depth }),
+            IntegratorKind::Normals => Box::new(Normals),
+            IntegratorKind::Depth { max_distance } => Box::new(Depth {
+                max_distance: T::from(max_distance).unwrap(),
+            }),
+            IntegratorKind::Albedo => Box::new(Albedo),
+            IntegratorKind::AmbientOcclusion { radius } => Box::new(AmbientOcclusion {
+                radius: T::from(radius).unwrap(),
+            }),
+            IntegratorKind::BounceHeatmap { max_rays } => Box::new(BounceHeatmap {
+                path: PathTracer { max_depth },
+                max_rays,
+            }),
+        }
+    }
+}
+
+/// Find the first surface a camera ray hits, if any.
+fn first_hit<T: Float>(
+    ray: &Ray<T>,
+    world: &dyn Hittable<T>,
+    rec: &mut HitRecord<T>,
+    rng: &mut Rng,
+) -> bool {
+    rec.ray_count += 1;
+
+    world.hit(ray, T::from(0.001).unwrap(), T::infinity(), rec, rng)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn debug_integrators_show_the_first_hit() {
+    use crate::material::{Lambertian, Material};
+    use crate::objects::sphere::Sphere;
+    use crate::random::DEFAULT_SEED;
+    use crate::vec::{Point3, Vec3};
+    use std::sync::Arc;
+
+    let albedo = Color {
+        x: 0.8,
+        y: 0.4,
+        z: 0.2,
+    };
+    let material: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(albedo));
+    let ball = |z, radius| -> Box<dyn Hittable<f64>> {
+        Box::new(Sphere {
+            center: Point3 { x: 0.0, y: 0.0, z },
+            radius,
+            material: material.clone(),
+        })
+    };
+
+    let world = HittableList::new(vec![ball(0.0, 1.0)]);
+    let lights = HittableList::new(Vec::new());
+    let environment = Environment::sky();
+
+    // Looking down -z at the sphere from 4 units away, hitting it 3 units in front of the camera.
+    let ray = Ray {
+        origin: Point3 {
+            x: 0.0,
+            y: 0.0,
+            z: 4.0,
+        },
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -2.0,
+        },
+        time: 0.0,
+    };
+    let miss = Ray {
+        direction: Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        ..ray
+    };
+
+    let color = |kind: IntegratorKind, ray: &Ray<f64>, world: &HittableList<f64>| {
+        let mut rec = HitRecord::new(material.clone());
+        let mut rng = Rng::new(DEFAULT_SEED);
+
+        kind.build(5)
+            .radiance(ray, world, &lights, &environment, &mut rec, &mut rng)
+    };
+
+    let normal = color(IntegratorKind::Normals, &ray, &world);
+    assert!(
+        (normal
+            - Color {
+                x: 0.5,
+                y: 0.5,
+                z: 1.0
+            })
+        .length()
+            < 1e-9
+    );
+    assert_eq!(color(IntegratorKind::Normals, &miss, &world), Color::zero());
+
+    let depth = color(IntegratorKind::Depth { max_distance: 6.0 }, &ray, &world);
+    assert!((depth.x - 0.5).abs() < 1e-9);
+
+    let surface = color(IntegratorKind::Albedo, &ray, &world);
+    assert!((surface - albedo).length() < 1e-9);
+
+    // Inside a bigger ball, every ambient occlusion ray is blocked within 1.2 units.
+    let ao = IntegratorKind::AmbientOcclusion { radius: 2.0 };
+    let shell = HittableList::new(vec![ball(0.0, 1.0), ball(0.0, 1.5)]);
+    let inside = Ray {
+        origin: Point3 {
+            x: 0.0,
+            y: 0.0,
+            z: 1.2,
+        },
+        ..ray
+    };
+    assert_eq!(color(ao, &ray, &world), Color::one());
+    assert_eq!(color(ao, &inside, &shell), Color::zero());
+    assert_eq!(color(ao, &miss, &world), Color::one());
+
+    // A miss casts one ray, a quarter of the way from blue to red, or all the way at one ray.
+    let heat = |max_rays| color(IntegratorKind::BounceHeatmap { max_rays }, &miss, &world);
+    assert_eq!(
+        heat(4),
+        Color {
+            x: 0.0,
+            y: 0.5,
+            z: 0.5
+        }
+    );
+    assert_eq!(
+        heat(1),
+        Color {
+            x: 1.0,
+            y: 0.0,
+            z: 0.0
+        }
+    );
+
+    // Bouncing off the ball casts more rays than missing it.
+    let hit_heat = color(IntegratorKind::BounceHeatmap { max_rays: 4 }, &ray, &world);
+    assert!(hit_heat.x > 0.0 || hit_heat.y > 0.5);
+}
@@ -0,0 +1,32 @@
+use crate::environment::Environment;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::integrator::{first_hit, Integrator};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::Color;
+use num::Float;
+
+/// Shows the color of the first surface hit, without any lighting: the fraction of light it
+/// scatters, or for lights the light they give off.  Misses are black.
+pub struct Albedo;
+
+impl<T: Float> Integrator<T> for Albedo {
+    fn radiance(
+        &self,
+        ray: &Ray<T>,
+        world: &dyn Hittable<T>,
+        _lights: &HittableList<T>,
+        _environment: &Environment<T>,
+        rec: &mut HitRecord<T>,
+        rng: &mut Rng,
+    ) -> Color<T> {
+        if !first_hit(ray, world, rec, rng) {
+            return Color::zero();
+        }
+
+        match rec.material.scatter(ray, rec, rng) {
+            Some(scatter) => scatter.weight(),
+            None => rec.material.emitted(rec),
+        }
+    }
+}
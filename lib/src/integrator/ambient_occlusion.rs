@@ -0,0 +1,58 @@
+use crate::environment::Environment;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::integrator::{first_hit, Integrator};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::{Color, Vec3};
+use num::Float;
+
+/// Shows how open the first surface hit is.  Each sample casts one ray in a cosine-weighted
+/// direction from the hit, which is white if nothing is within `radius` and black otherwise.
+/// Misses are white.  Shadow acne shows up as dark speckles on open surfaces.
+pub struct AmbientOcclusion<T: Float> {
+    pub radius: T,
+}
+
+impl<T: Float + Send + Sync> Integrator<T> for AmbientOcclusion<T> {
+    fn radiance(
+        &self,
+        ray: &Ray<T>,
+        world: &dyn Hittable<T>,
+        _lights: &HittableList<T>,
+        _environment: &Environment<T>,
+        rec: &mut HitRecord<T>,
+        rng: &mut Rng,
+    ) -> Color<T> {
+        if !first_hit(ray, world, rec, rng) {
+            return Color::one();
+        }
+
+        let direction = rec.normal + Vec3::<T>::random_unit_vector(rng);
+
+        let direction = if direction.near_zero() {
+            rec.normal
+        } else {
+            direction.unit()
+        };
+
+        let occlusion_ray = Ray {
+            origin: rec.p,
+            direction,
+            time: ray.time,
+        };
+
+        rec.ray_count += 1;
+
+        if world.hit(
+            &occlusion_ray,
+            T::from(0.001).unwrap(),
+            self.radius,
+            rec,
+            rng,
+        ) {
+            Color::zero()
+        } else {
+            Color::one()
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::environment::Environment;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::integrator::{Integrator, PathTracer};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::Color;
+use num::Float;
+use std::fmt::Debug;
+
+/// Shows how many rays, shadow rays included, the path tracer casts for each sample.  Few are blue,
+/// half of `max_rays` green and `max_rays` or more red.
+pub struct BounceHeatmap {
+    pub path: PathTracer,
+    pub max_rays: u32,
+}
+
+impl<T: Float + Debug + Send + Sync> Integrator<T> for BounceHeatmap {
+    fn radiance(
+        &self,
+        ray: &Ray<T>,
+        world: &dyn Hittable<T>,
+        lights: &HittableList<T>,
+        environment: &Environment<T>,
+        rec: &mut HitRecord<T>,
+        rng: &mut Rng,
+    ) -> Color<T> {
+        let before = rec.ray_count;
+
+        self.path
+            .radiance(ray, world, lights, environment, rec, rng);
+
+        let rays = (rec.ray_count - before) as f64;
+        let heat = (rays / self.max_rays.max(1) as f64).min(1.0);
+
+        heat_color(T::from(heat).unwrap())
+    }
+}
+
+/// Map [0, 1] to blue, then green, then red.
+fn heat_color<T: Float>(heat: T) -> Color<T> {
+    let two = T::from(2.0).unwrap();
+
+    Color {
+        x: (heat * two - T::one()).max(T::zero()),
+        y: T::one() - (heat * two - T::one()).abs(),
+        z: (T::one() - heat * two).max(T::zero()),
+    }
+}
@@ -0,0 +1,33 @@
+use crate::environment::Environment;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::integrator::{first_hit, Integrator};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::Color;
+use num::Float;
+
+/// Shows the distance to the first surface hit, white at the camera fading linearly to black at
+/// `max_distance` and beyond.
+pub struct Depth<T: Float> {
+    pub max_distance: T,
+}
+
+impl<T: Float + Send + Sync> Integrator<T> for Depth<T> {
+    fn radiance(
+        &self,
+        ray: &Ray<T>,
+        world: &dyn Hittable<T>,
+        _lights: &HittableList<T>,
+        _environment: &Environment<T>,
+        rec: &mut HitRecord<T>,
+        rng: &mut Rng,
+    ) -> Color<T> {
+        if !first_hit(ray, world, rec, rng) {
+            return Color::zero();
+        }
+
+        let distance = rec.t * ray.direction.length();
+
+        Color::one() * (T::one() - distance / self.max_distance).max(T::zero())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::environment::Environment;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::integrator::{first_hit, Integrator};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::Color;
+use num::Float;
+
+/// Shows the normal of the first surface hit, on the side facing the camera, mapped from [-1, 1]
+/// to [0, 1].  Misses are black.
+pub struct Normals;
+
+impl<T: Float> Integrator<T> for Normals {
+    fn radiance(
+        &self,
+        ray: &Ray<T>,
+        world: &dyn Hittable<T>,
+        _lights: &HittableList<T>,
+        _environment: &Environment<T>,
+        rec: &mut HitRecord<T>,
+        rng: &mut Rng,
+    ) -> Color<T> {
+        if !first_hit(ray, world, rec, rng) {
+            return Color::zero();
+        }
+
+        (rec.normal.unit() + Color::one()) * T::from(0.5).unwrap()
+    }
+}
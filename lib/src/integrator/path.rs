@@ -0,0 +1,28 @@
+use crate::environment::Environment;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::integrator::Integrator;
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::Color;
+use num::Float;
+use std::fmt::Debug;
+
+/// Path tracing with light sampling and Russian roulette, following paths of up to `max_depth`
+/// bounces.  See `Ray::color`.
+pub struct PathTracer {
+    pub max_depth: i32,
+}
+
+impl<T: Float + Debug + Send + Sync> Integrator<T> for PathTracer {
+    fn radiance(
+        &self,
+        ray: &Ray<T>,
+        world: &dyn Hittable<T>,
+        lights: &HittableList<T>,
+        environment: &Environment<T>,
+        rec: &mut HitRecord<T>,
+        rng: &mut Rng,
+    ) -> Color<T> {
+        ray.color(rec, world, lights, environment, self.max_depth, rng)
+    }
+}
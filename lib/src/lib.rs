@@ -1,18 +1,18 @@
+pub mod aabb;
+pub mod bvh;
 pub mod camera;
+pub mod environment;
 pub mod hit;
+pub mod integrator;
 pub mod material;
+pub mod obj;
 pub mod objects;
 pub mod random;
 pub mod ray;
+pub mod render;
+pub mod scene_file;
 pub mod scenes;
+pub mod texture;
+pub mod transform;
 pub mod vec;
 pub mod write;
-
-use lazy_static::lazy_static;
-// use simple_mutex::Mutex;
-// use std::sync::Mutex;
-use spin_sync::Mutex;
-
-lazy_static! {
-    static ref RNG: Mutex<u64> = Mutex::new(0xda942042e4dd58b5);
-}
@@ -1,22 +1,62 @@
 mod dielectric;
+mod diffuse_light;
+mod isotropic;
 mod lambertian;
 mod metal;
 
 pub use dielectric::Dielectric;
+pub use diffuse_light::DiffuseLight;
+pub use isotropic::Isotropic;
 pub use lambertian::Lambertian;
 pub use metal::Metal;
 
 use crate::hit::HitRecord;
+use crate::random::Rng;
 use crate::ray::Ray;
-use crate::vec::Color;
+use crate::vec::{Color, Vec3};
 use num::traits::Float;
 
-pub trait Material<T: Float> {
-    fn scatter(
-        &self,
-        r_in: &Ray<T>,
-        rec: &HitRecord<T>,
-        attenuation: &mut Color<T>,
-        scattered: &mut Ray<T>,
-    ) -> bool;
+/// How a material scattered a ray.
+pub struct ScatterRecord<T: Float> {
+    pub ray: Ray<T>,
+    /// The BSDF times the cosine between the new direction and the normal.  For a specular bounce,
+    /// which only ever goes one way, the fraction of light carried along the ray instead.
+    pub value: Color<T>,
+    /// Probability density, per unit solid angle, of picking the ray's direction, or None for a
+    /// specular bounce.  Specular bounces can't be combined with aiming at lights.
+    pub pdf: Option<T>,
+}
+
+impl<T: Float> ScatterRecord<T> {
+    /// Get the factor to scale the light arriving along the scattered ray by.
+    pub fn weight(&self) -> Color<T> {
+        match self.pdf {
+            Some(pdf) => self.value / pdf,
+            None => self.value,
+        }
+    }
+}
+
+pub trait Material<T: Float>: Send + Sync {
+    /// Pick a direction to scatter an incoming ray in, or return None if it's absorbed.
+    fn scatter(&self, r_in: &Ray<T>, rec: &HitRecord<T>, rng: &mut Rng)
+        -> Option<ScatterRecord<T>>;
+
+    /// Get the BSDF times the cosine factor for light arriving from the unit vector `direction`
+    /// and leaving back along `r_in`.  This is what lets the renderer pick directions itself, like
+    /// toward lights.  Specular materials return zero, since no other direction contributes.
+    fn eval(&self, _r_in: &Ray<T>, _rec: &HitRecord<T>, _direction: &Vec3<T>) -> Color<T> {
+        Color::zero()
+    }
+
+    /// Get the probability density, per unit solid angle, of `scatter` picking the unit vector
+    /// `direction`.  Zero for specular materials.
+    fn scattering_pdf(&self, _r_in: &Ray<T>, _rec: &HitRecord<T>, _direction: &Vec3<T>) -> T {
+        T::zero()
+    }
+
+    /// Get the light given off at a hit point.  Most materials don't give off any.
+    fn emitted(&self, _rec: &HitRecord<T>) -> Color<T> {
+        Color::zero()
+    }
 }
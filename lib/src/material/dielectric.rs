@@ -1,29 +1,38 @@
 use crate::hit::HitRecord;
-use crate::material::Material;
-use crate::random::random_float;
+use crate::material::{Material, ScatterRecord};
+use crate::random::Rng;
 use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
 use crate::vec::Color;
 use num::traits::Float;
+use std::sync::Arc;
 
 pub struct Dielectric<T: Float> {
     pub ir: T,
-    pub albedo: Color<T>,
+    pub albedo: Arc<dyn Texture<T>>,
+}
+
+impl<T: Float + Send + Sync + 'static> Dielectric<T> {
+    pub fn new(ir: T, albedo: Color<T>) -> Dielectric<T> {
+        Dielectric::textured(ir, Arc::new(SolidColor { color: albedo }))
+    }
+
+    pub fn textured(ir: T, albedo: Arc<dyn Texture<T>>) -> Dielectric<T> {
+        Dielectric { ir, albedo }
+    }
 }
 
 trait DielectricReflectance<T> {
     fn reflectance(&self, cosine: T, ref_idx: T) -> T;
 }
 
-impl<T: Float> Material<T> for Dielectric<T> {
+impl<T: Float + Send + Sync> Material<T> for Dielectric<T> {
     fn scatter(
         &self,
         r_in: &Ray<T>,
         rec: &HitRecord<T>,
-        attenuation: &mut Color<T>,
-        scattered: &mut Ray<T>,
-    ) -> bool {
-        *attenuation = self.albedo;
-
+        rng: &mut Rng,
+    ) -> Option<ScatterRecord<T>> {
         let refraction_ratio = if rec.front_face {
             T::one() / self.ir
         } else {
@@ -36,7 +45,7 @@ impl<T: Float> Material<T> for Dielectric<T> {
         let sin_theta = (T::one() - cos_theta * cos_theta).sqrt();
 
         let cannot_refract = refraction_ratio * sin_theta > T::one();
-        let should_reflect = self.reflectance(cos_theta, refraction_ratio) > random_float();
+        let should_reflect = self.reflectance(cos_theta, refraction_ratio) > rng.random_float();
 
         let direction = if cannot_refract || should_reflect {
             unit_direction.reflect(rec.normal)
@@ -44,12 +53,15 @@ impl<T: Float> Material<T> for Dielectric<T> {
             unit_direction.refract(rec.normal, refraction_ratio)
         };
 
-        *scattered = Ray {
-            origin: rec.p,
-            direction,
-        };
-
-        true
+        Some(ScatterRecord {
+            ray: Ray {
+                origin: rec.p,
+                direction,
+                time: r_in.time,
+            },
+            value: self.albedo.value(rec.u, rec.v, &rec.p),
+            pdf: None,
+        })
     }
 }
 
@@ -0,0 +1,26 @@
+use crate::hit::HitRecord;
+use crate::material::{Material, ScatterRecord};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::Color;
+use num::traits::Float;
+
+/// A material that gives off light and doesn't reflect any.
+pub struct DiffuseLight<T: Float> {
+    pub emit: Color<T>,
+}
+
+impl<T: Float + Send + Sync> Material<T> for DiffuseLight<T> {
+    fn scatter(
+        &self,
+        _r_in: &Ray<T>,
+        _rec: &HitRecord<T>,
+        _rng: &mut Rng,
+    ) -> Option<ScatterRecord<T>> {
+        None
+    }
+
+    fn emitted(&self, _rec: &HitRecord<T>) -> Color<T> {
+        self.emit
+    }
+}
@@ -0,0 +1,54 @@
+use crate::hit::HitRecord;
+use crate::material::{Material, ScatterRecord};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
+use crate::vec::{Color, Vec3};
+use num::traits::Float;
+use std::sync::Arc;
+
+/// Scatters light equally in every direction.  This is the phase function of fog and smoke, used
+/// as the material of a `ConstantMedium`.
+pub struct Isotropic<T: Float> {
+    pub albedo: Arc<dyn Texture<T>>,
+}
+
+impl<T: Float + Send + Sync + 'static> Isotropic<T> {
+    pub fn new(albedo: Color<T>) -> Isotropic<T> {
+        Isotropic::textured(Arc::new(SolidColor { color: albedo }))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture<T>>) -> Isotropic<T> {
+        Isotropic { albedo }
+    }
+}
+
+impl<T: Float + Send + Sync> Material<T> for Isotropic<T> {
+    fn scatter(
+        &self,
+        r_in: &Ray<T>,
+        rec: &HitRecord<T>,
+        rng: &mut Rng,
+    ) -> Option<ScatterRecord<T>> {
+        let ray = Ray {
+            origin: rec.p,
+            direction: Vec3::<T>::random_unit_vector(rng),
+            time: r_in.time,
+        };
+
+        Some(ScatterRecord {
+            value: self.eval(r_in, rec, &ray.direction),
+            pdf: Some(self.scattering_pdf(r_in, rec, &ray.direction)),
+            ray,
+        })
+    }
+
+    /// There's no surface to take a cosine with, so this is just the phase function.
+    fn eval(&self, r_in: &Ray<T>, rec: &HitRecord<T>, direction: &Vec3<T>) -> Color<T> {
+        self.albedo.value(rec.u, rec.v, &rec.p) * self.scattering_pdf(r_in, rec, direction)
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray<T>, _rec: &HitRecord<T>, _direction: &Vec3<T>) -> T {
+        T::one() / T::from(4.0 * std::f64::consts::PI).unwrap()
+    }
+}
@@ -1,38 +1,62 @@
 use crate::hit::HitRecord;
-use crate::material::Material;
+use crate::material::{Material, ScatterRecord};
+use crate::random::Rng;
 use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
 use crate::vec::{Color, Vec3};
 use num::traits::Float;
+use std::sync::Arc;
 
 pub struct Lambertian<T: Float> {
-    pub albedo: Color<T>,
+    pub albedo: Arc<dyn Texture<T>>,
 }
 
-impl<T: Float> Material<T> for Lambertian<T> {
+impl<T: Float + Send + Sync + 'static> Lambertian<T> {
+    pub fn new(albedo: Color<T>) -> Lambertian<T> {
+        Lambertian::textured(Arc::new(SolidColor { color: albedo }))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture<T>>) -> Lambertian<T> {
+        Lambertian { albedo }
+    }
+}
+
+impl<T: Float + Send + Sync> Material<T> for Lambertian<T> {
     fn scatter(
         &self,
-        _r_in: &Ray<T>,
+        r_in: &Ray<T>,
         rec: &HitRecord<T>,
-        attenuation: &mut Color<T>,
-        scattered: &mut Ray<T>,
-    ) -> bool {
-        let scatter_direction = rec.normal + Vec3::<T>::random_unit_vector();
+        rng: &mut Rng,
+    ) -> Option<ScatterRecord<T>> {
+        let scatter_direction = rec.normal + Vec3::<T>::random_unit_vector(rng);
 
         let scatter_direction = if scatter_direction.near_zero() {
             rec.normal
         } else {
-            scatter_direction
+            scatter_direction.unit()
         };
 
-        let scatter_ray = Ray {
+        let ray = Ray {
             origin: rec.p,
             direction: scatter_direction,
+            time: r_in.time,
         };
 
-        *scattered = scatter_ray;
+        Some(ScatterRecord {
+            value: self.eval(r_in, rec, &ray.direction),
+            pdf: Some(self.scattering_pdf(r_in, rec, &ray.direction)),
+            ray,
+        })
+    }
+
+    fn eval(&self, r_in: &Ray<T>, rec: &HitRecord<T>, direction: &Vec3<T>) -> Color<T> {
+        self.albedo.value(rec.u, rec.v, &rec.p) * self.scattering_pdf(r_in, rec, direction)
+    }
 
-        *attenuation = self.albedo;
+    /// Directions are picked in proportion to their cosine with the normal.
+    fn scattering_pdf(&self, _r_in: &Ray<T>, rec: &HitRecord<T>, direction: &Vec3<T>) -> T {
+        let cosine = rec.normal.dot(direction);
 
-        true
+        cosine.max(T::zero()) / T::from(std::f64::consts::PI).unwrap()
     }
 }
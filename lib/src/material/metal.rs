@@ -1,31 +1,51 @@
 use crate::hit::HitRecord;
-use crate::material::Material;
+use crate::material::{Material, ScatterRecord};
+use crate::random::Rng;
 use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
 use crate::vec::{Color, Vec3};
 use num::traits::Float;
+use std::sync::Arc;
 
 pub struct Metal<T: Float> {
-    pub albedo: Color<T>,
+    pub albedo: Arc<dyn Texture<T>>,
     pub fuzz: T,
 }
 
-impl<T: Float> Material<T> for Metal<T> {
+impl<T: Float + Send + Sync + 'static> Metal<T> {
+    pub fn new(albedo: Color<T>, fuzz: T) -> Metal<T> {
+        Metal::textured(Arc::new(SolidColor { color: albedo }), fuzz)
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture<T>>, fuzz: T) -> Metal<T> {
+        Metal { albedo, fuzz }
+    }
+}
+
+impl<T: Float + Send + Sync> Material<T> for Metal<T> {
     fn scatter(
         &self,
         r_in: &Ray<T>,
         rec: &HitRecord<T>,
-        attenuation: &mut Color<T>,
-        scattered: &mut Ray<T>,
-    ) -> bool {
+        rng: &mut Rng,
+    ) -> Option<ScatterRecord<T>> {
         let reflected = r_in.direction.unit().reflect(rec.normal);
 
-        *scattered = Ray {
+        let ray = Ray {
             origin: rec.p,
-            direction: reflected + Vec3::<T>::random_in_unit_sphere() * self.fuzz,
+            direction: reflected + Vec3::<T>::random_in_unit_sphere(rng) * self.fuzz,
+            time: r_in.time,
         };
 
-        *attenuation = self.albedo;
+        if ray.direction.dot(&rec.normal) <= T::zero() {
+            return None;
+        }
 
-        scattered.direction.dot(&rec.normal) > T::zero()
+        // Fuzzy reflections are treated as specular too, since their density isn't known.
+        Some(ScatterRecord {
+            ray,
+            value: self.albedo.value(rec.u, rec.v, &rec.p),
+            pdf: None,
+        })
     }
 }
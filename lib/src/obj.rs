@@ -0,0 +1,640 @@
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                          WAVEFRONT OBJ                                         //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Material libraries referenced by OBJ files.
+pub mod mtl;
+
+use crate::aabb::Aabb;
+use crate::hit::{HitRecord, Hittable};
+use crate::material::{Lambertian, Material};
+use crate::objects::mesh::{MeshData, TriangleMesh};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::{Color, Point3, Vec3};
+use image::ImageError;
+use num::Float;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A model loaded from a `.obj` file.  Faces are split into one mesh per group and material.
+pub struct ObjModel<T: Float> {
+    pub groups: Vec<ObjGroup<T>>,
+}
+
+/// The faces of one group that share a material.
+pub struct ObjGroup<T: Float> {
+    /// The name from the most recent `g` or `o` line, or "default".
+    pub name: String,
+    /// The name from the most recent `usemtl` line, if any.
+    pub material: Option<String>,
+    pub mesh: TriangleMesh<T>,
+}
+
+#[derive(Debug)]
+pub enum ObjError {
+    /// A file couldn't be read.
+    Io { path: PathBuf, source: io::Error },
+    /// A line couldn't be understood.
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// A face refers to a vertex, texture coordinate or normal that doesn't exist.
+    BadIndex {
+        path: PathBuf,
+        line: usize,
+        index: i64,
+    },
+    /// A `usemtl` names a material that isn't in any loaded library.
+    UnknownMaterial {
+        path: PathBuf,
+        line: usize,
+        name: String,
+    },
+    /// A material's texture map couldn't be loaded.
+    Texture { path: PathBuf, source: ImageError },
+    /// The file has no faces, so there's nothing to render.
+    NoFaces { path: PathBuf },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io { path, source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+            ObjError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            ObjError::BadIndex { path, line, index } => {
+                write!(
+                    f,
+                    "{}:{}: index {} is out of range",
+                    path.display(),
+                    line,
+                    index
+                )
+            }
+            ObjError::UnknownMaterial { path, line, name } => {
+                write!(
+                    f,
+                    "{}:{}: unknown material '{}'",
+                    path.display(),
+                    line,
+                    name
+                )
+            }
+            ObjError::Texture { path, source } => {
+                write!(f, "could not load texture {}: {}", path.display(), source)
+            }
+            ObjError::NoFaces { path } => write!(f, "{}: no faces", path.display()),
+        }
+    }
+}
+
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ObjError::Io { source, .. } => Some(source),
+            ObjError::Texture { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Float + Debug + Send + Sync + 'static> ObjModel<T> {
+    /// Load a `.obj` file and any `.mtl` files it references, which are looked up relative to the
+    /// `.obj` file.  Faces with no material are grey `Lambertian`.
+    pub fn load(path: &Path) -> Result<ObjModel<T>, ObjError> {
+        let text = fs::read_to_string(path).map_err(|source| ObjError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        ObjModel::parse(&text, path, |name| mtl::load(&dir.join(name)))
+    }
+
+    /// Parse the text of a `.obj` file.  `load_mtl` is called with the file name from each
+    /// `mtllib` line, and `path` is only used in error messages.
+    pub fn parse(
+        text: &str,
+        path: &Path,
+        mut load_mtl: impl FnMut(&str) -> Result<Vec<mtl::MtlMaterial>, ObjError>,
+    ) -> Result<ObjModel<T>, ObjError> {
+        let mut positions: Vec<Point3<T>> = Vec::new();
+        let mut normals: Vec<Vec3<T>> = Vec::new();
+        let mut uvs: Vec<(T, T)> = Vec::new();
+
+        let mut materials: HashMap<String, Arc<dyn Material<T>>> = HashMap::new();
+        let default_material: Arc<dyn Material<T>> = Arc::new(Lambertian::new(Color {
+            x: T::from(0.8).unwrap(),
+            y: T::from(0.8).unwrap(),
+            z: T::from(0.8).unwrap(),
+        }));
+
+        let mut groups = Vec::new();
+        let mut current = GroupBuilder::new("default".to_string(), None, default_material.clone());
+
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let parse_error = |message: String| ObjError::Parse {
+                path: path.to_path_buf(),
+                line: line_number,
+                message,
+            };
+
+            let line = line.split('#').next().unwrap().trim();
+            let mut words = line.split_whitespace();
+
+            let keyword = match words.next() {
+                Some(keyword) => keyword,
+                None => continue,
+            };
+
+            let args: Vec<&str> = words.collect();
+
+            match keyword {
+                "v" => match parse_numbers(&args).map_err(parse_error)?[..] {
+                    [x, y, z, ..] => positions.push(Point3 {
+                        x: T::from(x).unwrap(),
+                        y: T::from(y).unwrap(),
+                        z: T::from(z).unwrap(),
+                    }),
+                    _ => return Err(parse_error("v needs three coordinates".to_string())),
+                },
+                "vn" => match parse_numbers(&args).map_err(parse_error)?[..] {
+                    [x, y, z] => normals.push(Vec3 {
+                        x: T::from(x).unwrap(),
+                        y: T::from(y).unwrap(),
+                        z: T::from(z).unwrap(),
+                    }),
+                    _ => return Err(parse_error("vn needs three coordinates".to_string())),
+                },
+                "vt" => match parse_numbers(&args).map_err(parse_error)?[..] {
+                    [u] => uvs.push((T::from(u).unwrap(), T::zero())),
+                    [u, v, ..] => uvs.push((T::from(u).unwrap(), T::from(v).unwrap())),
+                    _ => return Err(parse_error("vt needs a coordinate".to_string())),
+                },
+                "f" => {
+                    if args.len() < 3 {
+                        return Err(parse_error("f needs at least three vertices".to_string()));
+                    }
+
+                    let counts = (positions.len(), uvs.len(), normals.len());
+                    let face = args
+                        .iter()
+                        .map(|arg| {
+                            parse_face_vertex(arg, counts).map_err(|err| match err {
+                                FaceError::Syntax => {
+                                    parse_error(format!("'{}' is not a face vertex", arg))
+                                }
+                                FaceError::Index(index) => ObjError::BadIndex {
+                                    path: path.to_path_buf(),
+                                    line: line_number,
+                                    index,
+                                },
+                            })
+                        })
+                        .collect::<Result<Vec<FaceVertex>, ObjError>>()?;
+
+                    let face: Vec<usize> = face
+                        .iter()
+                        .map(|v| current.vertex(v, &positions, &uvs, &normals))
+                        .collect();
+
+                    // Split polygons into a fan of triangles around the first vertex, which is
+                    // correct for the convex polygons exporters produce.
+                    for k in 1..face.len() - 1 {
+                        current.data.indices.push([face[0], face[k], face[k + 1]]);
+                    }
+                }
+                "g" | "o" => {
+                    let name = if args.is_empty() {
+                        "default".to_string()
+                    } else {
+                        args.join(" ")
+                    };
+                    let next = GroupBuilder::new(
+                        name,
+                        current.material_name.clone(),
+                        current.material.clone(),
+                    );
+                    groups.extend(std::mem::replace(&mut current, next).finish());
+                }
+                "usemtl" => {
+                    let name = args.join(" ");
+                    let material =
+                        materials
+                            .get(&name)
+                            .cloned()
+                            .ok_or_else(|| ObjError::UnknownMaterial {
+                                path: path.to_path_buf(),
+                                line: line_number,
+                                name: name.clone(),
+                            })?;
+                    let next = GroupBuilder::new(current.name.clone(), Some(name), material);
+                    groups.extend(std::mem::replace(&mut current, next).finish());
+                }
+                "mtllib" => {
+                    for file in args.iter() {
+                        for m in load_mtl(file)? {
+                            let material = m.material()?;
+                            materials.insert(m.name, material);
+                        }
+                    }
+                }
+                // Smoothing groups, lines, points and so on are ignored.
+                _ => {}
+            }
+        }
+
+        groups.extend(current.finish());
+
+        if groups.is_empty() {
+            return Err(ObjError::NoFaces {
+                path: path.to_path_buf(),
+            });
+        }
+
+        Ok(ObjModel { groups })
+    }
+}
+
+impl<T: Float> ObjModel<T> {
+    pub fn triangle_count(&self) -> usize {
+        self.groups.iter().map(|g| g.mesh.triangle_count()).sum()
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for ObjModel<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool {
+        let mut hit_anything = false;
+        let mut closest_so_far = t_max;
+
+        for group in self.groups.iter() {
+            if group.mesh.hit(r, t_min, closest_so_far, rec, rng) {
+                hit_anything = true;
+                closest_so_far = rec.t;
+            }
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        self.groups
+            .iter()
+            .filter_map(|g| g.mesh.bounding_box())
+            .reduce(|a, b| a.surrounding(&b))
+    }
+}
+
+/// Indices into the position, texture coordinate and normal lists, counting from zero.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct FaceVertex {
+    position: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+enum FaceError {
+    Syntax,
+    Index(i64),
+}
+
+/// Parse a face vertex like "3", "3/1", "3//2" or "3/1/2".  `counts` is how many positions, UVs
+/// and normals have been read so far, which negative indices count back from.
+fn parse_face_vertex(
+    s: &str,
+    (positions, uvs, normals): (usize, usize, usize),
+) -> Result<FaceVertex, FaceError> {
+    let mut parts = s.split('/');
+
+    let position = match parts.next() {
+        Some(p) if !p.is_empty() => resolve_index(p, positions)?,
+        _ => return Err(FaceError::Syntax),
+    };
+
+    let uv = match parts.next() {
+        Some(p) if !p.is_empty() => Some(resolve_index(p, uvs)?),
+        _ => None,
+    };
+
+    let normal = match parts.next() {
+        Some(p) if !p.is_empty() => Some(resolve_index(p, normals)?),
+        _ => None,
+    };
+
+    if parts.next().is_some() {
+        return Err(FaceError::Syntax);
+    }
+
+    Ok(FaceVertex {
+        position,
+        uv,
+        normal,
+    })
+}
+
+/// Turn a one-based (or negative, counting from the end) OBJ index into a zero-based one.
+fn resolve_index(s: &str, count: usize) -> Result<usize, FaceError> {
+    let index: i64 = s.parse().map_err(|_| FaceError::Syntax)?;
+
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        count as i64 + index
+    };
+
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        Err(FaceError::Index(index))
+    } else {
+        Ok(resolved as usize)
+    }
+}
+
+pub(crate) fn parse_numbers(args: &[&str]) -> Result<Vec<f64>, String> {
+    args.iter()
+        .map(|s| s.parse().map_err(|_| format!("'{}' is not a number", s)))
+        .collect()
+}
+
+/// Collects the faces of one group and material into a mesh.  OBJ indexes positions, UVs and
+/// normals separately, so each distinct combination becomes one mesh vertex.
+struct GroupBuilder<T: Float> {
+    name: String,
+    material_name: Option<String>,
+    material: Arc<dyn Material<T>>,
+    data: MeshData<T>,
+    normals: Vec<Vec3<T>>,
+    uvs: Vec<(T, T)>,
+    all_normals: bool,
+    all_uvs: bool,
+    vertices: HashMap<FaceVertex, usize>,
+}
+
+impl<T: Float + Debug + Send + Sync + 'static> GroupBuilder<T> {
+    fn new(
+        name: String,
+        material_name: Option<String>,
+        material: Arc<dyn Material<T>>,
+    ) -> GroupBuilder<T> {
+        GroupBuilder {
+            name,
+            material_name,
+            material,
+            data: MeshData::default(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            all_normals: true,
+            all_uvs: true,
+            vertices: HashMap::new(),
+        }
+    }
+
+    /// Get the mesh index for a face vertex, adding it if it's new.
+    fn vertex(
+        &mut self,
+        v: &FaceVertex,
+        positions: &[Point3<T>],
+        uvs: &[(T, T)],
+        normals: &[Vec3<T>],
+    ) -> usize {
+        if let Some(&index) = self.vertices.get(v) {
+            return index;
+        }
+
+        let index = self.data.positions.len();
+
+        self.data.positions.push(positions[v.position]);
+        self.uvs
+            .push(v.uv.map_or((T::zero(), T::zero()), |i| uvs[i]));
+        self.normals
+            .push(v.normal.map_or(Vec3::zero(), |i| normals[i]));
+        self.all_uvs &= v.uv.is_some();
+        self.all_normals &= v.normal.is_some();
+
+        self.vertices.insert(*v, index);
+
+        index
+    }
+
+    /// Build the group's mesh, or None if it has no faces.  Normals and UVs are only kept if every
+    /// vertex has them.
+    fn finish(mut self) -> Option<ObjGroup<T>> {
+        if self.data.indices.is_empty() {
+            return None;
+        }
+
+        if self.all_normals {
+            self.data.normals = Some(self.normals);
+        }
+
+        if self.all_uvs {
+            self.data.uvs = Some(self.uvs);
+        }
+
+        Some(ObjGroup {
+            name: self.name,
+            material: self.material_name,
+            mesh: TriangleMesh::new(self.data, self.material),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn obj_groups_and_triangulation() {
+    let obj = "
+        mtllib box.mtl
+        v 0 0 0
+        v 1 0 0
+        v 1 1 0
+        v 0 1 0
+        v 0 0 1
+        vt 0 0
+        vt 1 0
+        vt 1 1
+        vt 0 1
+        vn 0 0 1
+
+        o quad
+        usemtl shiny
+        f 1/1/1 2/2/1 3/3/1 4/4/1   # one quad, two triangles
+
+        g triangle
+        f -5 -4 -1
+
+        g wood
+        usemtl Dark Wood
+        f 1 2 5
+    ";
+
+    let mtl = "
+        newmtl shiny
+        Ks 0.9 0.9 0.9
+        Ns 500
+        illum 3
+
+        newmtl Dark Wood
+        Kd 0.3 0.2 0.1
+    ";
+
+    let path = Path::new("test.obj");
+    let model: ObjModel<f64> = ObjModel::parse(obj, path, |name| {
+        assert_eq!(name, "box.mtl");
+        mtl::parse(mtl, Path::new(name))
+    })
+    .unwrap();
+
+    assert_eq!(model.groups.len(), 3);
+    assert_eq!(model.triangle_count(), 4);
+
+    assert_eq!(model.groups[0].name, "quad");
+    assert_eq!(model.groups[0].material.as_deref(), Some("shiny"));
+    assert_eq!(model.groups[0].mesh.triangle_count(), 2);
+
+    // The material carries over into the next group.
+    assert_eq!(model.groups[1].name, "triangle");
+    assert_eq!(model.groups[1].material.as_deref(), Some("shiny"));
+
+    // Material names can have spaces in them.
+    assert_eq!(model.groups[2].name, "wood");
+    assert_eq!(model.groups[2].material.as_deref(), Some("Dark Wood"));
+
+    let ray = Ray {
+        origin: Point3 {
+            x: 0.9,
+            y: 0.9,
+            z: 2.0,
+        },
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time: 0.0,
+    };
+
+    let mut rec = HitRecord::new(Arc::new(Lambertian::new(Color::one())));
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+
+    assert!(model.hit(&ray, 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert_eq!(rec.t, 2.0);
+    assert!((rec.u - 0.9).abs() < 1e-12 && (rec.v - 0.9).abs() < 1e-12);
+}
+
+#[test]
+fn obj_errors() {
+    let no_mtl = |_: &str| Ok(Vec::new());
+    let path = Path::new("test.obj");
+
+    let bad_index = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n";
+    assert!(matches!(
+        ObjModel::<f64>::parse(bad_index, path, no_mtl),
+        Err(ObjError::BadIndex {
+            line: 4,
+            index: 4,
+            ..
+        })
+    ));
+
+    let bad_number = "v 0 zero 0\n";
+    assert!(matches!(
+        ObjModel::<f64>::parse(bad_number, path, no_mtl),
+        Err(ObjError::Parse { line: 1, .. })
+    ));
+
+    let no_faces = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+    assert!(matches!(
+        ObjModel::<f64>::parse(no_faces, path, no_mtl),
+        Err(ObjError::NoFaces { .. })
+    ));
+
+    let unknown_material = "usemtl missing\n";
+    assert!(matches!(
+        ObjModel::<f64>::parse(unknown_material, path, no_mtl),
+        Err(ObjError::UnknownMaterial { .. })
+    ));
+
+    assert!(matches!(
+        ObjModel::<f64>::load(Path::new("/no/such/model.obj")),
+        Err(ObjError::Io { .. })
+    ));
+}
+
+#[test]
+fn mtl_texture_maps() {
+    use crate::random::{Rng, DEFAULT_SEED};
+    use mtl::MtlMaterial;
+
+    let mtl = "
+        newmtl label
+        map_Kd -s 1 1 1 textures/label.png
+
+        newmtl decal
+        map_Kd -imfchan l -clamp on -o 0.5 0.5 old label.png
+        d 0.99
+    ";
+
+    let materials = mtl::parse(mtl, Path::new("models/box.mtl")).unwrap();
+
+    assert_eq!(
+        materials[0].diffuse_map.as_deref(),
+        Some(Path::new("models/textures/label.png"))
+    );
+    assert!(matches!(
+        materials[0].material::<f64>(),
+        Err(ObjError::Texture { .. })
+    ));
+
+    // Options all come off the front, and the file name can have spaces in it.
+    assert_eq!(
+        materials[1].diffuse_map.as_deref(),
+        Some(Path::new("models/old label.png"))
+    );
+
+    // A nearly opaque material stays diffuse rather than turning into glass, which has no pdf.
+    let decal = MtlMaterial {
+        diffuse_map: None,
+        ..materials[1].clone()
+    };
+    let ray = Ray {
+        origin: Point3::zero(),
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time: 0.0,
+    };
+    let mut rec = HitRecord::new(decal.material::<f64>().unwrap());
+    rec.set_face_normal(
+        &ray,
+        Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+        },
+    );
+    let scatter = rec
+        .material
+        .scatter(&ray, &rec, &mut Rng::new(DEFAULT_SEED));
+
+    assert!(scatter.unwrap().pdf.is_some());
+}
@@ -0,0 +1,219 @@
+/////////////////////////
+//  MATERIAL LIBRARIES  //
+/////////////////////////
+
+use super::{parse_numbers, ObjError};
+use crate::material::{Dielectric, Lambertian, Material, Metal};
+use crate::texture::ImageTexture;
+use crate::vec::Color;
+use num::Float;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The parts of a `.mtl` material that the renderer understands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MtlMaterial {
+    pub name: String,
+    /// Kd
+    pub diffuse: [f64; 3],
+    /// map_Kd, relative to the directory of the `.mtl` file.  Used instead of Kd.
+    pub diffuse_map: Option<PathBuf>,
+    /// Ks
+    pub specular: [f64; 3],
+    /// Ns, the Phong exponent.
+    pub shininess: f64,
+    /// Ni
+    pub ior: f64,
+    /// d, or one minus Tr.
+    pub dissolve: f64,
+    /// Tf
+    pub transmission: Option<[f64; 3]>,
+    /// illum
+    pub illum: Option<u32>,
+}
+
+impl MtlMaterial {
+    fn new(name: &str) -> MtlMaterial {
+        MtlMaterial {
+            name: name.to_string(),
+            diffuse: [0.8, 0.8, 0.8],
+            diffuse_map: None,
+            specular: [0.0, 0.0, 0.0],
+            shininess: 0.0,
+            ior: 1.5,
+            dissolve: 1.0,
+            transmission: None,
+            illum: None,
+        }
+    }
+
+    /// Map the material onto one of ours.  Transparent materials (illum 4, 6, 7 or 9, or a dissolve
+    /// below one half) become `Dielectric`, reflective ones (illum 3 or 5) become `Metal` with a
+    /// fuzz derived from the shininess, and everything else is `Lambertian`.  Fails if the
+    /// diffuse texture map can't be loaded.
+    pub fn material<T: Float + Send + Sync + 'static>(
+        &self,
+    ) -> Result<Arc<dyn Material<T>>, ObjError> {
+        let color = |c: [f64; 3]| Color {
+            x: T::from(c[0]).unwrap(),
+            y: T::from(c[1]).unwrap(),
+            z: T::from(c[2]).unwrap(),
+        };
+
+        let material = match self.illum {
+            Some(4) | Some(6) | Some(7) | Some(9) => self.dielectric(color),
+            // Slightly see-through materials, like decals, stay opaque rather than turning into
+            // clear glass.
+            _ if self.dissolve < 0.5 => self.dielectric(color),
+            Some(3) | Some(5) => Arc::new(Metal::new(
+                color(self.specular),
+                // A rough match between the Phong exponent and how blurry reflections look.
+                T::from((2.0 / (self.shininess + 2.0)).sqrt().min(1.0)).unwrap(),
+            )),
+            _ => match &self.diffuse_map {
+                Some(path) => {
+                    let texture = ImageTexture::load(path).map_err(|source| ObjError::Texture {
+                        path: path.clone(),
+                        source,
+                    })?;
+
+                    Arc::new(Lambertian::textured(Arc::new(texture)))
+                }
+                None => Arc::new(Lambertian::new(color(self.diffuse))),
+            },
+        };
+
+        Ok(material)
+    }
+
+    fn dielectric<T: Float + Send + Sync + 'static>(
+        &self,
+        color: impl Fn([f64; 3]) -> Color<T>,
+    ) -> Arc<dyn Material<T>> {
+        Arc::new(Dielectric::new(
+            T::from(self.ior).unwrap(),
+            color(self.transmission.unwrap_or([1.0, 1.0, 1.0])),
+        ))
+    }
+}
+
+/// Read every material in a `.mtl` file.
+pub fn load(path: &Path) -> Result<Vec<MtlMaterial>, ObjError> {
+    let text = fs::read_to_string(path).map_err(|source| ObjError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    parse(&text, path)
+}
+
+/// Parse the text of a `.mtl` file.  `path` is used in error messages and to find texture maps.
+pub fn parse(text: &str, path: &Path) -> Result<Vec<MtlMaterial>, ObjError> {
+    let mut materials: Vec<MtlMaterial> = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let error = |message: String| ObjError::Parse {
+            path: path.to_path_buf(),
+            line: line_number,
+            message,
+        };
+
+        let line = line.split('#').next().unwrap().trim();
+        let mut words = line.split_whitespace();
+
+        let keyword = match words.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        let args: Vec<&str> = words.collect();
+
+        if keyword == "newmtl" {
+            // Names can have spaces in them, just as `usemtl` reads them.
+            match args.join(" ") {
+                name if name.is_empty() => return Err(error("newmtl needs a name".to_string())),
+                name => materials.push(MtlMaterial::new(&name)),
+            }
+            continue;
+        }
+
+        let current = match materials.last_mut() {
+            Some(current) => current,
+            // Anything before the first newmtl has nothing to apply to.
+            None => continue,
+        };
+
+        match keyword {
+            "Kd" => current.diffuse = color(&args).map_err(error)?,
+            "Ks" => current.specular = color(&args).map_err(error)?,
+            "Tf" => current.transmission = Some(color(&args).map_err(error)?),
+            "Ns" => current.shininess = scalar(&args).map_err(error)?,
+            "Ni" => current.ior = scalar(&args).map_err(error)?,
+            "d" => current.dissolve = scalar(&args).map_err(error)?,
+            "Tr" => current.dissolve = 1.0 - scalar(&args).map_err(error)?,
+            "map_Kd" => match map_file_name(&args) {
+                Some(file) => {
+                    current.diffuse_map =
+                        Some(path.parent().unwrap_or_else(|| Path::new("")).join(file))
+                }
+                None => return Err(error("map_Kd needs a file name".to_string())),
+            },
+            "illum" => {
+                current.illum = Some(
+                    args.first()
+                        .and_then(|s| s.parse().ok())
+                        .ok_or_else(|| error("illum needs a whole number".to_string()))?,
+                )
+            }
+            // Texture maps, ambient color and so on aren't supported.
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+/// Get the file name from a texture map statement's arguments.  Options like `-s 1 1 1` come
+/// first, and the rest is the file name, which can have spaces in it.
+fn map_file_name(args: &[&str]) -> Option<String> {
+    let mut rest = args;
+
+    while let Some(option) = rest.first().filter(|arg| arg.starts_with('-')) {
+        rest = &rest[1..];
+
+        // Most options take numbers or on/off, but -imfchan takes a channel letter.
+        if *option == "-imfchan" && !rest.is_empty() {
+            rest = &rest[1..];
+        }
+
+        while let Some(arg) = rest.first() {
+            if arg.parse::<f64>().is_err() && *arg != "on" && *arg != "off" {
+                break;
+            }
+            rest = &rest[1..];
+        }
+    }
+
+    match rest.join(" ") {
+        name if name.is_empty() => None,
+        name => Some(name),
+    }
+}
+
+fn scalar(args: &[&str]) -> Result<f64, String> {
+    match parse_numbers(args)?[..] {
+        [n, ..] => Ok(n),
+        _ => Err("expected a number".to_string()),
+    }
+}
+
+/// Parse an "r g b" color.  A single value is used for all three channels.
+fn color(args: &[&str]) -> Result<[f64; 3], String> {
+    match parse_numbers(args)?[..] {
+        [r, g, b, ..] => Ok([r, g, b]),
+        [v] => Ok([v, v, v]),
+        _ => Err("expected a color".to_string()),
+    }
+}
@@ -1 +1,8 @@
+pub mod box_shape;
+pub mod constant_medium;
+pub mod instance;
+pub mod mesh;
+pub mod moving_sphere;
+pub mod quad;
 pub mod sphere;
+pub mod triangle;
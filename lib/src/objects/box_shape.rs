@@ -0,0 +1,147 @@
+/////////////
+//  BOXES  //
+/////////////
+
+use crate::aabb::Aabb;
+use crate::hit::HitRecord;
+use crate::hit::Hittable;
+use crate::material::Material;
+use crate::objects::quad::Quad;
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::{Point3, Vec3};
+use num::Float;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// An axis-aligned box made of six quads, all facing out.
+pub struct BoxShape<T: Float> {
+    sides: [Quad<T>; 6],
+    bbox: Aabb<T>,
+}
+
+impl<T: Float + Debug> BoxShape<T> {
+    /// Create the box with opposite corners `a` and `b`.
+    pub fn new(a: Point3<T>, b: Point3<T>, material: Arc<dyn Material<T>>) -> BoxShape<T> {
+        let min = Point3 {
+            x: a.x.min(b.x),
+            y: a.y.min(b.y),
+            z: a.z.min(b.z),
+        };
+        let max = Point3 {
+            x: a.x.max(b.x),
+            y: a.y.max(b.y),
+            z: a.z.max(b.z),
+        };
+
+        let dx = Vec3 {
+            x: max.x - min.x,
+            y: T::zero(),
+            z: T::zero(),
+        };
+        let dy = Vec3 {
+            x: T::zero(),
+            y: max.y - min.y,
+            z: T::zero(),
+        };
+        let dz = Vec3 {
+            x: T::zero(),
+            y: T::zero(),
+            z: max.z - min.z,
+        };
+
+        let corner = |x: T, y: T, z: T| Point3 { x, y, z };
+
+        let sides = [
+            // Front and back
+            Quad::new(corner(min.x, min.y, max.z), dx, dy, material.clone()),
+            Quad::new(corner(max.x, min.y, min.z), -dx, dy, material.clone()),
+            // Right and left
+            Quad::new(corner(max.x, min.y, max.z), -dz, dy, material.clone()),
+            Quad::new(corner(min.x, min.y, min.z), dz, dy, material.clone()),
+            // Top and bottom
+            Quad::new(corner(min.x, max.y, max.z), dx, -dz, material.clone()),
+            Quad::new(corner(min.x, min.y, min.z), dx, dz, material),
+        ];
+
+        BoxShape {
+            sides,
+            bbox: Aabb::new(min, max),
+        }
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for BoxShape<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool {
+        let mut hit_anything = false;
+        let mut closest = t_max;
+
+        for side in self.sides.iter() {
+            if side.hit(r, t_min, closest, rec, rng) {
+                hit_anything = true;
+                closest = rec.t;
+            }
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        Some(self.bbox.pad(T::from(1e-4).unwrap()))
+    }
+
+    /// Sampling picks one of the six sides at random, so the density is their average.
+    fn pdf_value(&self, origin: &Point3<T>, direction: &Vec3<T>) -> T {
+        let total = self.sides.iter().fold(T::zero(), |sum, side| {
+            sum + side.pdf_value(origin, direction)
+        });
+
+        total / T::from(self.sides.len()).unwrap()
+    }
+
+    fn random(&self, origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        let index = (rng.random_float::<f64>() * self.sides.len() as f64) as usize;
+
+        self.sides[index.min(self.sides.len() - 1)].random(origin, rng)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn box_faces_point_out() {
+    use crate::material::Lambertian;
+    use crate::vec::Color;
+
+    let material: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(Color::one()));
+    let cube = BoxShape::new(Point3::zero(), Point3::one(), material.clone());
+    let center = Point3::one() * 0.5;
+
+    let mut rec = HitRecord::new(material);
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+
+    // From outside along each axis, the hit is on the near face, facing the ray.
+    for axis in 0..3 {
+        for &sign in [-1.0, 1.0].iter() {
+            let mut direction = Vec3::zero();
+            match axis {
+                0 => direction.x = sign,
+                1 => direction.y = sign,
+                _ => direction.z = sign,
+            }
+
+            let ray = Ray {
+                origin: center - direction * 2.0,
+                direction,
+                time: 0.0,
+            };
+
+            assert!(cube.hit(&ray, 0.001, f64::INFINITY, &mut rec, &mut rng));
+            assert_eq!(rec.t, 1.5);
+            assert!(rec.front_face);
+            assert_eq!(rec.normal, -direction);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+///////////////////////
+//  CONSTANT MEDIUM  //
+///////////////////////
+
+use crate::aabb::Aabb;
+use crate::hit::HitRecord;
+use crate::hit::Hittable;
+use crate::material::Material;
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::Vec3;
+use num::Float;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Fog or smoke of even thickness filling a closed shape.  A ray passing through is scattered at a
+/// random distance, more likely sooner the denser the medium is, or passes straight through if that
+/// distance is past the far side.
+pub struct ConstantMedium<T: Float> {
+    boundary: Arc<dyn Hittable<T>>,
+    density: T,
+    /// Usually `Isotropic`, which scatters light in every direction.
+    phase_function: Arc<dyn Material<T>>,
+}
+
+impl<T: Float + Debug> ConstantMedium<T> {
+    /// Fill `boundary` with a medium.  Density is the chance of scattering per unit of distance
+    /// travelled, and should be greater than zero, or nothing is ever scattered.  The boundary must
+    /// be closed and convex, like a sphere or box.
+    pub fn new(
+        boundary: Arc<dyn Hittable<T>>,
+        density: T,
+        phase_function: Arc<dyn Material<T>>,
+    ) -> ConstantMedium<T> {
+        ConstantMedium {
+            boundary,
+            density,
+            phase_function,
+        }
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for ConstantMedium<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool {
+        // Without any density, nothing is ever scattered.
+        if self.density <= T::zero() {
+            return false;
+        }
+
+        // Find where the ray's line enters and leaves the boundary.  The record is only touched on
+        // a hit, since it may hold a closer object's hit.
+        let mut entry = HitRecord::new(self.phase_function.clone());
+        let mut exit = HitRecord::new(self.phase_function.clone());
+
+        if !self
+            .boundary
+            .hit(r, T::neg_infinity(), T::infinity(), &mut entry, rng)
+        {
+            return false;
+        }
+
+        if !self.boundary.hit(
+            r,
+            entry.t + T::from(0.0001).unwrap(),
+            T::infinity(),
+            &mut exit,
+            rng,
+        ) {
+            return false;
+        }
+
+        let t_enter = entry.t.max(t_min);
+        let t_exit = exit.t.min(t_max);
+
+        if t_enter >= t_exit {
+            return false;
+        }
+
+        let ray_length = r.direction.length();
+        let distance_inside = (t_exit - t_enter) * ray_length;
+        let hit_distance = -(T::one() - rng.random_float::<T>()).ln() / self.density;
+
+        if hit_distance > distance_inside {
+            return false;
+        }
+
+        rec.t = t_enter + hit_distance / ray_length;
+        rec.p = r.at(rec.t);
+
+        // A point inside a medium has no surface, so these are arbitrary.
+        rec.normal = Vec3 {
+            x: T::one(),
+            y: T::zero(),
+            z: T::zero(),
+        };
+        rec.front_face = true;
+        rec.u = T::zero();
+        rec.v = T::zero();
+        rec.material = self.phase_function.clone();
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        self.boundary.bounding_box()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn constant_medium_scatters_by_density() {
+    use crate::material::Isotropic;
+    use crate::objects::sphere::Sphere;
+    use crate::random::DEFAULT_SEED;
+    use crate::vec::{Color, Point3};
+
+    let material: Arc<dyn Material<f64>> = Arc::new(Isotropic::new(Color::one()));
+    let ball = |density| {
+        let boundary = Arc::new(Sphere {
+            center: Point3::zero(),
+            radius: 1.0,
+            material: material.clone(),
+        });
+
+        ConstantMedium::new(boundary, density, material.clone())
+    };
+
+    // Count how many rays through the middle of the ball, two units of medium, are scattered.
+    let scattered = |medium: &ConstantMedium<f64>| {
+        let mut rng = Rng::new(DEFAULT_SEED);
+        let mut rec = HitRecord::new(material.clone());
+
+        (0..10_000)
+            .filter(|_| {
+                let ray = Ray {
+                    origin: Point3 {
+                        x: rng.random_float_in_range(-0.01, 0.01),
+                        y: rng.random_float_in_range(-0.01, 0.01),
+                        z: 5.0,
+                    },
+                    direction: Vec3 {
+                        x: 0.0,
+                        y: 0.0,
+                        z: -1.0,
+                    },
+                    time: 0.0,
+                };
+
+                let hit = medium.hit(&ray, 0.001, f64::INFINITY, &mut rec, &mut rng);
+                assert!(!hit || (4.0..=6.0).contains(&rec.t));
+                hit
+            })
+            .count() as f64
+            / 10_000.0
+    };
+
+    // The chance of getting through is e^(-density * distance).
+    let thin = scattered(&ball(0.1));
+    let thick = scattered(&ball(1.0));
+    assert!((thin - (1.0 - (-0.2f64).exp())).abs() < 0.02, "{}", thin);
+    assert!((thick - (1.0 - (-2.0f64).exp())).abs() < 0.02, "{}", thick);
+
+    // Rays starting inside the medium can scatter too.
+    let mut rec = HitRecord::new(material.clone());
+    let mut rng = Rng::new(DEFAULT_SEED);
+    let inside = Ray {
+        origin: Point3::zero(),
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time: 0.0,
+    };
+    assert!(ball(1000.0).hit(&inside, 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert!(rec.t < 0.1);
+
+    // Without any density, nothing is scattered.
+    assert_eq!(scattered(&ball(0.0)), 0.0);
+    assert_eq!(scattered(&ball(-1.0)), 0.0);
+}
@@ -0,0 +1,313 @@
+////////////////
+//  INSTANCE  //
+////////////////
+
+use crate::aabb::Aabb;
+use crate::hit::HitRecord;
+use crate::hit::Hittable;
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::transform::{Pose, Transform};
+use crate::vec::{Point3, Vec3};
+use num::Float;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// An object placed in the scene with a transform.  The object is shared, so one mesh can be
+/// placed many times without copying it.
+pub struct Instance<T: Float> {
+    object: Arc<dyn Hittable<T>>,
+    /// From the object's own coordinates to the scene's.
+    transform: Transform<T>,
+    /// From the scene's coordinates to the object's.
+    inverse: Transform<T>,
+    /// The poses at times 0 and 1 of an animated instance, which are blended at each ray's time
+    /// in place of `transform`.
+    motion: Option<(Pose<T>, Pose<T>)>,
+    bbox: Option<Aabb<T>>,
+}
+
+/// Number of steps an animated instance's bounding box is built from.
+const MOTION_STEPS: usize = 32;
+
+impl<T: Float + Debug> Instance<T> {
+    pub fn new(object: Arc<dyn Hittable<T>>, transform: Transform<T>) -> Instance<T> {
+        let bbox = object.bounding_box().map(|b| transform.bounding_box(&b));
+
+        Instance {
+            object,
+            transform,
+            inverse: transform.inverse(),
+            motion: None,
+            bbox,
+        }
+    }
+
+    /// Create an instance that moves from the `start` pose at time 0 to the `end` pose at time 1.
+    pub fn animated(object: Arc<dyn Hittable<T>>, start: Pose<T>, end: Pose<T>) -> Instance<T> {
+        let bbox = object.bounding_box().map(|b| {
+            let steps = T::from(MOTION_STEPS).unwrap();
+            let at = |i: usize| {
+                let pose = start.lerp(&end, T::from(i).unwrap() / steps);
+                pose.transform().bounding_box(&b)
+            };
+
+            let swept = (1..=MOTION_STEPS).fold(at(0), |bbox, i| bbox.surrounding(&at(i)));
+
+            // Between steps, rotating corners bulge out along their arcs by up to r(1 - cos(θ/2)).
+            let turn = (end.rotate - start.rotate) / steps;
+            let step_angle = (turn.x.abs() + turn.y.abs() + turn.z.abs()).to_radians();
+            let largest = |v: Vec3<T>| v.x.abs().max(v.y.abs()).max(v.z.abs());
+            let farthest = Vec3 {
+                x: b.min.x.abs().max(b.max.x.abs()),
+                y: b.min.y.abs().max(b.max.y.abs()),
+                z: b.min.z.abs().max(b.max.z.abs()),
+            };
+            let radius = farthest.length() * largest(start.scale).max(largest(end.scale));
+            let bulge = radius * (T::one() - (step_angle / T::from(2.0).unwrap()).cos());
+            let margin = Vec3 {
+                x: bulge,
+                y: bulge,
+                z: bulge,
+            };
+
+            Aabb::new(swept.min - margin, swept.max + margin)
+        });
+
+        let transform = start.transform();
+
+        Instance {
+            object,
+            transform,
+            inverse: transform.inverse(),
+            motion: Some((start, end)),
+            bbox,
+        }
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for Instance<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool {
+        let (transform, inverse) = match &self.motion {
+            Some((start, end)) => {
+                let transform = start.lerp(end, r.time).transform();
+                (transform, transform.inverse())
+            }
+            None => (self.transform, self.inverse),
+        };
+
+        // The ray's direction isn't normalized, so t means the same thing on both sides.
+        if !self.object.hit(&inverse.ray(r), t_min, t_max, rec, rng) {
+            return false;
+        }
+
+        rec.p = transform.point(&rec.p);
+        rec.normal = transform.normal(&rec.normal).unit();
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        self.bbox
+    }
+
+    /// Sampling happens in the object's own coordinates, where both the origin and the direction
+    /// are carried along.  Uneven scaling bunches directions up, so the object's density is
+    /// corrected by how much solid angle shrinks or grows on the way.  Animated instances are
+    /// sampled at their starting pose.
+    fn pdf_value(&self, origin: &Point3<T>, direction: &Vec3<T>) -> T {
+        let local = self.inverse.vector(&direction.unit());
+        let length = local.length();
+
+        // For a unit direction ω, solid angle scales by |det M⁻¹| / |M⁻¹ω|³.
+        let jacobian = self.inverse.determinant().abs() / (length * length * length);
+
+        self.object
+            .pdf_value(&self.inverse.point(origin), &(local / length))
+            * jacobian
+    }
+
+    fn random(&self, origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        self.transform
+            .vector(&self.object.random(&self.inverse.point(origin), rng))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn instance_moves_and_turns_an_object() {
+    use crate::material::{Lambertian, Material};
+    use crate::objects::box_shape::BoxShape;
+    use crate::vec::{Color, Point3, Vec3};
+
+    let material: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(Color::one()));
+    let cube: Arc<dyn Hittable<f64>> = Arc::new(BoxShape::new(
+        Point3::zero(),
+        Point3::one(),
+        material.clone(),
+    ));
+
+    // Turned 45° about y, the cube's footprint is a diamond with corners at the origin and
+    // (0.71, 0.71) along its old x = 0 face.  Then it moves to x = 10.
+    let instance = Instance::new(
+        cube.clone(),
+        Transform::rotate_y(45.0).then(&Transform::translate(Vec3 {
+            x: 10.0,
+            y: 0.0,
+            z: 0.0,
+        })),
+    );
+
+    let ray = Ray {
+        origin: Point3 {
+            x: 10.5,
+            y: 0.5,
+            z: 5.0,
+        },
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time: 0.0,
+    };
+
+    let mut rec = HitRecord::new(material);
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+    let half = 0.5f64.sqrt();
+
+    assert!(instance.hit(&ray, 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert!((rec.t - 4.5).abs() < 1e-9);
+    assert!((rec.p - ray.at(4.5)).length() < 1e-9);
+    assert!(
+        (rec.normal
+            - Vec3 {
+                x: -half,
+                y: 0.0,
+                z: half
+            })
+        .length()
+            < 1e-9
+    );
+    assert!(rec.front_face);
+
+    // The original cube is still where it was.
+    assert!(!cube.hit(&ray, 0.001, f64::INFINITY, &mut rec, &mut rng));
+
+    let bbox = instance.bounding_box().unwrap();
+    assert!((bbox.max.x - (10.0 + 2.0f64.sqrt())).abs() < 1e-3);
+}
+
+#[test]
+fn animated_instance_follows_ray_time() {
+    use crate::material::{Lambertian, Material};
+    use crate::objects::box_shape::BoxShape;
+    use crate::vec::{Color, Point3};
+
+    let material: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(Color::one()));
+    let cube: Arc<dyn Hittable<f64>> = Arc::new(BoxShape::new(
+        Point3::zero(),
+        Point3::one(),
+        material.clone(),
+    ));
+
+    // The cube slides from the origin to x = 4 while making a half turn about y.
+    let start = Pose::default();
+    let end = Pose {
+        rotate: Vec3 {
+            x: 0.0,
+            y: 180.0,
+            z: 0.0,
+        },
+        translate: Vec3 {
+            x: 4.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        ..start
+    };
+    let instance = Instance::animated(cube, start, end);
+
+    let ray = |x, time| Ray {
+        origin: Point3 { x, y: 0.5, z: 5.0 },
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time,
+    };
+
+    let mut rec = HitRecord::new(material);
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+
+    // At the start the cube's nearest face is at z = 1.  By the end it has turned around to lie
+    // between x = 3 and 4 and z = -1 and 0.
+    assert!(instance.hit(&ray(0.5, 0.0), 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert!((rec.t - 4.0).abs() < 1e-9);
+    assert!(!instance.hit(&ray(0.5, 1.0), 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert!(instance.hit(&ray(3.5, 1.0), 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert!((rec.t - 5.0).abs() < 1e-9);
+
+    // The bounding box covers the whole sweep, including the cube's corners swinging out at the
+    // quarter turn.
+    let bbox = instance.bounding_box().unwrap();
+    assert!(bbox.min.x <= 0.0 && bbox.max.x >= 4.0);
+    assert!(bbox.min.z <= -1.0 && bbox.max.z >= 1.0);
+}
+
+#[test]
+fn unevenly_scaled_instance_pdf_integrates_to_one() {
+    use crate::material::{Lambertian, Material};
+    use crate::objects::sphere::Sphere;
+    use crate::vec::Color;
+    use std::f64::consts::PI;
+
+    let material: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(Color::one()));
+    let sphere: Arc<dyn Hittable<f64>> = Arc::new(Sphere {
+        center: Point3::zero(),
+        radius: 1.0,
+        material,
+    });
+
+    // Stretched along x, the sphere becomes an ellipsoid seen from the side.
+    let instance = Instance::new(
+        sphere,
+        Transform::scale(Vec3 {
+            x: 2.0,
+            y: 1.0,
+            z: 1.0,
+        }),
+    );
+    let origin = Point3 {
+        x: 0.0,
+        y: 0.0,
+        z: 3.0,
+    };
+
+    // Add up the density over a grid that's even in z and the angle around it, so every cell
+    // covers the same solid angle.
+    let (rows, columns) = (1000, 1000);
+    let cell = 4.0 * PI / (rows * columns) as f64;
+    let mut total = 0.0;
+
+    for row in 0..rows {
+        let z = 1.0 - 2.0 * (row as f64 + 0.5) / rows as f64;
+        let r = (1.0 - z * z).sqrt();
+
+        for column in 0..columns {
+            let phi = 2.0 * PI * (column as f64 + 0.5) / columns as f64;
+            let direction = Vec3 {
+                x: r * phi.cos(),
+                y: r * phi.sin(),
+                z,
+            };
+            total += instance.pdf_value(&origin, &direction) * cell;
+        }
+    }
+
+    assert!((total - 1.0).abs() < 0.01, "{}", total);
+}
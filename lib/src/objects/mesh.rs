@@ -0,0 +1,229 @@
+/////////////////////
+//  TRIANGLE MESH  //
+/////////////////////
+
+use crate::aabb::Aabb;
+use crate::bvh::FlatBvh;
+use crate::hit::HitRecord;
+use crate::hit::{Hittable, HittableList};
+use crate::material::Material;
+use crate::objects::triangle;
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::{Point3, Vec3};
+use num::Float;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Vertex and index buffers for a triangle mesh.  Normals and UVs, if present, have one entry per
+/// position and use the same indices.
+#[derive(Debug, Clone)]
+pub struct MeshData<T: Float> {
+    pub positions: Vec<Point3<T>>,
+    pub normals: Option<Vec<Vec3<T>>>,
+    pub uvs: Option<Vec<(T, T)>>,
+    /// Three indices into the vertex buffers for each triangle.
+    pub indices: Vec<[usize; 3]>,
+}
+
+impl<T: Float> Default for MeshData<T> {
+    fn default() -> Self {
+        MeshData {
+            positions: Vec::new(),
+            normals: None,
+            uvs: None,
+            indices: Vec::new(),
+        }
+    }
+}
+
+/// Many triangles sharing one set of vertex buffers and one material.  The triangles are kept in
+/// their own BVH, so a mesh can be added to a scene as a single object.
+pub struct TriangleMesh<T: Float> {
+    bvh: FlatBvh<T>,
+    triangle_count: usize,
+}
+
+/// The buffers and material shared by every triangle of a mesh.
+struct SharedMesh<T: Float> {
+    data: MeshData<T>,
+    material: Arc<dyn Material<T>>,
+}
+
+/// One triangle of a mesh, referring to its vertices by index.
+struct MeshTriangle<T: Float> {
+    mesh: Arc<SharedMesh<T>>,
+    face: usize,
+}
+
+impl<T: Float + Debug + Send + Sync + 'static> TriangleMesh<T> {
+    /// Build a mesh and its BVH.
+    ///
+    /// Panics if any index is out of range of the positions, or if the normals or UVs don't have
+    /// one entry per position.
+    pub fn new(data: MeshData<T>, material: Arc<dyn Material<T>>) -> TriangleMesh<T> {
+        let vertex_count = data.positions.len();
+
+        assert!(
+            data.indices.iter().flatten().all(|&i| i < vertex_count),
+            "Mesh index out of range."
+        );
+        assert!(
+            data.normals.as_ref().map_or(vertex_count, Vec::len) == vertex_count,
+            "Mesh must have one normal per position."
+        );
+        assert!(
+            data.uvs.as_ref().map_or(vertex_count, Vec::len) == vertex_count,
+            "Mesh must have one UV per position."
+        );
+
+        let triangle_count = data.indices.len();
+        let mesh = Arc::new(SharedMesh { data, material });
+
+        let mut triangles = HittableList::new(Vec::with_capacity(triangle_count));
+
+        for face in 0..triangle_count {
+            triangles.add(Box::new(MeshTriangle {
+                mesh: mesh.clone(),
+                face,
+            }));
+        }
+
+        TriangleMesh {
+            bvh: FlatBvh::new(triangles),
+            triangle_count,
+        }
+    }
+}
+
+impl<T: Float> TriangleMesh<T> {
+    pub fn triangle_count(&self) -> usize {
+        self.triangle_count
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for TriangleMesh<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, rng: &mut Rng) -> bool {
+        self.bvh.hit(r, t_min, t_max, rec, rng)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        self.bvh.bounding_box()
+    }
+}
+
+impl<T: Float> MeshTriangle<T> {
+    fn vertices(&self) -> [Point3<T>; 3] {
+        let [a, b, c] = self.mesh.data.indices[self.face];
+        let positions = &self.mesh.data.positions;
+
+        [positions[a], positions[b], positions[c]]
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for MeshTriangle<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, _rng: &mut Rng) -> bool {
+        let vertices = self.vertices();
+
+        match triangle::intersect(r, &vertices, t_min, t_max) {
+            Some(hit) => {
+                let [a, b, c] = self.mesh.data.indices[self.face];
+                let data = &self.mesh.data;
+
+                let normals = data.normals.as_ref().map(|n| [n[a], n[b], n[c]]);
+                let uvs = data.uvs.as_ref().map(|uv| [uv[a], uv[b], uv[c]]);
+
+                triangle::fill_record(r, rec, &vertices, normals.as_ref(), uvs.as_ref(), hit);
+                rec.material = self.mesh.material.clone();
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        Some(triangle::bounding_box(&self.vertices()))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn mesh_matches_separate_triangles() {
+    use crate::material::Lambertian;
+    use crate::objects::triangle::Triangle;
+    use crate::random::{Rng, DEFAULT_SEED};
+    use crate::vec::Color;
+
+    let material: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(Color::one()));
+
+    // A bumpy grid of quads, two triangles each.
+    let size = 8;
+    let mut rng = Rng::new(DEFAULT_SEED);
+    let mut data = MeshData::default();
+
+    for i in 0..=size {
+        for j in 0..=size {
+            data.positions.push(Point3 {
+                x: i as f64,
+                y: rng.random_float_in_range(-0.3, 0.3),
+                z: j as f64,
+            });
+        }
+    }
+
+    for i in 0..size {
+        for j in 0..size {
+            let corner = i * (size + 1) + j;
+            data.indices.push([corner, corner + 1, corner + size + 1]);
+            data.indices
+                .push([corner + 1, corner + size + 2, corner + size + 1]);
+        }
+    }
+
+    let mut list = HittableList::new(Vec::new());
+
+    for [a, b, c] in data.indices.iter() {
+        list.add(Box::new(Triangle::new(
+            data.positions[*a],
+            data.positions[*b],
+            data.positions[*c],
+            material.clone(),
+        )));
+    }
+
+    let mesh = TriangleMesh::new(data, material.clone());
+    assert_eq!(mesh.triangle_count(), 2 * size * size);
+
+    for _ in 0..200 {
+        let ray = Ray {
+            origin: Point3 {
+                x: rng.random_float_in_range(-1.0, 9.0),
+                y: 5.0,
+                z: rng.random_float_in_range(-1.0, 9.0),
+            },
+            direction: Vec3 {
+                x: rng.random_float_in_range(-0.5, 0.5),
+                y: -1.0,
+                z: rng.random_float_in_range(-0.5, 0.5),
+            },
+            time: 0.0,
+        };
+
+        let mut mesh_rec = HitRecord::new(material.clone());
+        let mut list_rec = HitRecord::new(material.clone());
+
+        let mesh_hit = mesh.hit(&ray, 0.001, f64::INFINITY, &mut mesh_rec, &mut rng);
+        let list_hit = list.hit(&ray, 0.001, f64::INFINITY, &mut list_rec, &mut rng);
+
+        assert_eq!(mesh_hit, list_hit);
+
+        if mesh_hit {
+            assert_eq!(mesh_rec.t, list_rec.t);
+            assert_eq!((mesh_rec.u, mesh_rec.v), (list_rec.u, list_rec.v));
+        }
+    }
+}
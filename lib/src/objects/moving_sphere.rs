@@ -0,0 +1,142 @@
+/////////////////////
+//  MOVING SPHERE  //
+/////////////////////
+
+use crate::aabb::Aabb;
+use crate::hit::HitRecord;
+use crate::hit::Hittable;
+use crate::material::Material;
+use crate::objects::sphere::{hit_sphere, sphere_pdf_value, sphere_random};
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::{Point3, Vec3};
+use num::Float;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// A sphere moving in a straight line, from `center0` at time 0 to `center1` at time 1.  Rendered
+/// with the camera's shutter open over part of that interval, it comes out motion blurred.
+pub struct MovingSphere<T: Float> {
+    pub center0: Point3<T>,
+    pub center1: Point3<T>,
+    pub radius: T,
+    pub material: Arc<dyn Material<T>>,
+}
+
+impl<T: Float> MovingSphere<T> {
+    /// Get the center of the sphere at a point in time.
+    pub fn center(&self, time: T) -> Point3<T> {
+        self.center0 + (self.center1 - self.center0) * time
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for MovingSphere<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, _rng: &mut Rng) -> bool {
+        hit_sphere(
+            self.center(r.time),
+            self.radius,
+            &self.material,
+            r,
+            t_min,
+            t_max,
+            rec,
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        let r = Vec3 {
+            x: self.radius.abs(),
+            y: self.radius.abs(),
+            z: self.radius.abs(),
+        };
+
+        let start = Aabb::new(self.center0 - r, self.center0 + r);
+        let end = Aabb::new(self.center1 - r, self.center1 + r);
+
+        Some(start.surrounding(&end))
+    }
+
+    /// Light sampling doesn't know the time, so the sphere is sampled where it is at time 0.
+    /// Shadow rays cast at other times may miss it, which is noisier but still unbiased.
+    fn pdf_value(&self, origin: &Point3<T>, direction: &Vec3<T>) -> T {
+        sphere_pdf_value(self.center0, self.radius, &self.material, origin, direction)
+    }
+
+    fn random(&self, origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        sphere_random(self.center0, self.radius, origin, rng)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn moving_sphere_follows_ray_time() {
+    use crate::material::Lambertian;
+    use crate::vec::Color;
+
+    let material: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(Color::one()));
+    let sphere = MovingSphere {
+        center0: Point3::zero(),
+        center1: Point3 {
+            x: 4.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        radius: 1.0,
+        material: material.clone(),
+    };
+
+    // A ray down the z axis through x = 4 only hits the sphere once it has arrived.
+    let ray = |time| Ray {
+        origin: Point3 {
+            x: 4.0,
+            y: 0.0,
+            z: 5.0,
+        },
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time,
+    };
+
+    let mut rec = HitRecord::new(material);
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+    assert!(!sphere.hit(&ray(0.0), 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert!(sphere.hit(&ray(1.0), 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert_eq!(rec.t, 4.0);
+
+    let bbox = sphere.bounding_box().unwrap();
+    assert_eq!(bbox.min.x, -1.0);
+    assert_eq!(bbox.max.x, 5.0);
+
+    // As a light, it's aimed at where it is at time 0.
+    let origin = Point3 {
+        x: 0.0,
+        y: 5.0,
+        z: 0.0,
+    };
+    let mut rng = crate::random::Rng::new(crate::random::DEFAULT_SEED);
+
+    for _ in 0..100 {
+        let direction = sphere.random(&origin, &mut rng);
+        let toward = Ray {
+            origin,
+            direction,
+            time: 0.0,
+        };
+
+        assert!(sphere.hit(&toward, 0.001, f64::INFINITY, &mut rec, &mut rng));
+        assert!(sphere.pdf_value(&origin, &direction) > 0.0);
+    }
+
+    let away = Vec3 {
+        x: 0.0,
+        y: 1.0,
+        z: 0.0,
+    };
+    assert_eq!(sphere.pdf_value(&origin, &away), 0.0);
+}
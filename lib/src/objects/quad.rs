@@ -0,0 +1,193 @@
+////////////
+//  QUAD  //
+////////////
+
+use crate::aabb::Aabb;
+use crate::hit::HitRecord;
+use crate::hit::Hittable;
+use crate::material::Material;
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::{Point3, Vec3};
+use num::Float;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// A flat parallelogram with one corner at `q` and sides `u` and `v`.  The surface coordinates of
+/// a hit point are how far it is along `u` and `v`, from 0 to 1, and the front face is the side
+/// that `u × v` points out of.
+pub struct Quad<T: Float> {
+    q: Point3<T>,
+    u: Vec3<T>,
+    v: Vec3<T>,
+    material: Arc<dyn Material<T>>,
+    normal: Vec3<T>,
+    /// The plane is the points p with normal · p = d.
+    d: T,
+    /// Turns a point on the plane into its coordinates along u and v.
+    w: Vec3<T>,
+    area: T,
+}
+
+impl<T: Float + Debug> Quad<T> {
+    pub fn new(q: Point3<T>, u: Vec3<T>, v: Vec3<T>, material: Arc<dyn Material<T>>) -> Quad<T> {
+        let n = u.cross(&v);
+        let normal = n.unit();
+
+        Quad {
+            q,
+            u,
+            v,
+            material,
+            normal,
+            d: normal.dot(&q),
+            w: n / n.dot(&n),
+            area: n.length(),
+        }
+    }
+
+    /// Hit the quad, which needs no random numbers, so `pdf_value` can aim rays at it too.
+    fn hit_quad(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>) -> bool {
+        let denominator = self.normal.dot(&r.direction);
+
+        // The ray is parallel to the plane.
+        if denominator.abs() < T::from(1e-8).unwrap() {
+            return false;
+        }
+
+        let t = (self.d - self.normal.dot(&r.origin)) / denominator;
+
+        if t < t_min || t_max < t {
+            return false;
+        }
+
+        let p = r.at(t);
+        let planar = p - self.q;
+        let alpha = self.w.dot(&planar.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&planar));
+
+        let inside = |x: T| x >= T::zero() && x <= T::one();
+
+        if !inside(alpha) || !inside(beta) {
+            return false;
+        }
+
+        rec.t = t;
+        rec.p = p;
+        rec.u = alpha;
+        rec.v = beta;
+        rec.set_face_normal(r, self.normal);
+        rec.material = self.material.clone();
+
+        true
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for Quad<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, _rng: &mut Rng) -> bool {
+        self.hit_quad(r, t_min, t_max, rec)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        let corners = [self.q + self.u, self.q + self.v, self.q + self.u + self.v];
+
+        let bbox = corners.iter().fold(Aabb::new(self.q, self.q), |bbox, &c| {
+            bbox.surrounding(&Aabb::new(c, c))
+        });
+
+        // A quad lined up with an axis has no thickness along it.
+        Some(bbox.pad(T::from(1e-4).unwrap()))
+    }
+
+    fn pdf_value(&self, origin: &Point3<T>, direction: &Vec3<T>) -> T {
+        let ray = Ray {
+            origin: *origin,
+            direction: *direction,
+            time: T::zero(),
+        };
+        let mut rec = HitRecord::new(self.material.clone());
+
+        if !self.hit_quad(&ray, T::from(0.001).unwrap(), T::infinity(), &mut rec) {
+            return T::zero();
+        }
+
+        area_pdf(direction, rec.t, &self.normal, self.area)
+    }
+
+    fn random(&self, origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        let p = self.q + self.u * rng.random_float::<T>() + self.v * rng.random_float::<T>();
+
+        p - *origin
+    }
+}
+
+/// Convert the density of picking a point uniformly on a flat surface to a density per unit solid
+/// angle, for a ray along `direction` that meets the surface at `t`.
+pub(crate) fn area_pdf<T: Float>(direction: &Vec3<T>, t: T, normal: &Vec3<T>, area: T) -> T {
+    let length = direction.length();
+    let distance_squared = t * t * length * length;
+    let cosine = (direction.dot(normal) / length).abs();
+
+    if cosine <= T::zero() {
+        return T::zero();
+    }
+
+    distance_squared / (cosine * area)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn quad_hits_inside_its_edges() {
+    use crate::material::Lambertian;
+    use crate::vec::Color;
+
+    let material: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(Color::one()));
+
+    // A 2 × 1 rectangle in the plane z = -1, facing +z.
+    let quad = Quad::new(
+        Point3 {
+            x: -1.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        Vec3 {
+            x: 2.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        material.clone(),
+    );
+
+    let ray = |x, y| Ray {
+        origin: Point3 { x, y, z: 0.0 },
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time: 0.0,
+    };
+
+    let mut rec = HitRecord::new(material);
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+
+    assert!(quad.hit(&ray(0.5, 0.25), 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert_eq!(rec.t, 1.0);
+    assert_eq!((rec.u, rec.v), (0.75, 0.25));
+    assert!(rec.front_face);
+
+    assert!(!quad.hit(&ray(1.5, 0.5), 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert!(!quad.hit(&ray(0.0, -0.1), 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert!(!quad.hit(&ray(0.0, 0.5), 0.001, 0.5, &mut rec, &mut rng));
+
+    let bbox = quad.bounding_box().unwrap();
+    assert!(bbox.max.z > bbox.min.z);
+}
@@ -2,54 +2,207 @@
 //  SPHERE  //
 //////////////
 
+use crate::aabb::Aabb;
 use crate::hit::HitRecord;
 use crate::hit::Hittable;
 use crate::material::Material;
+use crate::random::Rng;
 use crate::ray::Ray;
-use crate::vec::Point3;
+use crate::vec::{Point3, Vec3};
 use num::Float;
 use std::fmt::Debug;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub struct Sphere<T: Float> {
     pub center: Point3<T>,
     pub radius: T,
-    pub material: Rc<dyn Material<T>>,
+    pub material: Arc<dyn Material<T>>,
 }
 
-impl<T: Float + Debug> Hittable<T> for Sphere<T> {
-    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>) -> bool {
-        let oc = r.origin - self.center;
-        let a = r.direction.length_squared();
-        let half_b = oc.dot(&r.direction);
-        let c = oc.length_squared() - self.radius * self.radius;
+impl<T: Float + Debug + Send + Sync> Hittable<T> for Sphere<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, _rng: &mut Rng) -> bool {
+        hit_sphere(
+            self.center,
+            self.radius,
+            &self.material,
+            r,
+            t_min,
+            t_max,
+            rec,
+        )
+    }
 
-        let discriminant = half_b * half_b - a * c;
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        let r = Vec3 {
+            x: self.radius.abs(),
+            y: self.radius.abs(),
+            z: self.radius.abs(),
+        };
 
-        if discriminant < T::zero() {
-            return false;
-        }
+        Some(Aabb::new(self.center - r, self.center + r))
+    }
+
+    /// Seen from outside, a sphere covers a cone of directions, which is sampled uniformly.
+    fn pdf_value(&self, origin: &Point3<T>, direction: &Vec3<T>) -> T {
+        sphere_pdf_value(self.center, self.radius, &self.material, origin, direction)
+    }
 
-        let sqrtd = discriminant.sqrt();
+    fn random(&self, origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        sphere_random(self.center, self.radius, origin, rng)
+    }
+}
+
+/// Get the density of `sphere_random` picking `direction` from `origin`.  Shared by spheres that
+/// stay put and ones that move.
+pub(crate) fn sphere_pdf_value<T: Float + Debug>(
+    center: Point3<T>,
+    radius: T,
+    material: &Arc<dyn Material<T>>,
+    origin: &Point3<T>,
+    direction: &Vec3<T>,
+) -> T {
+    let ray = Ray {
+        origin: *origin,
+        direction: *direction,
+        time: T::zero(),
+    };
+    let mut rec = HitRecord::new(material.clone());
+
+    if !hit_sphere(
+        center,
+        radius,
+        material,
+        &ray,
+        T::from(0.001).unwrap(),
+        T::infinity(),
+        &mut rec,
+    ) {
+        return T::zero();
+    }
 
-        // Find the nearest root that lies in the acceptable range.
-        let mut root = (-half_b - sqrtd) / a;
+    let two_pi = T::from(2.0 * std::f64::consts::PI).unwrap();
+
+    match cos_theta_max(center, radius, origin) {
+        Some(cos_theta_max) => T::one() / (two_pi * (T::one() - cos_theta_max)),
+        // From inside, every direction hits.
+        None => T::one() / (two_pi + two_pi),
+    }
+}
+
+/// Pick a direction from `origin` toward a sphere, uniformly within the cone it covers.
+pub(crate) fn sphere_random<T: Float>(
+    center: Point3<T>,
+    radius: T,
+    origin: &Point3<T>,
+    rng: &mut Rng,
+) -> Vec3<T> {
+    let cos_theta_max = match cos_theta_max(center, radius, origin) {
+        Some(cos_theta_max) => cos_theta_max,
+        None => return Vec3::random_unit_vector(rng),
+    };
+
+    let z = T::one() + rng.random_float::<T>() * (cos_theta_max - T::one());
+    let phi = T::from(2.0 * std::f64::consts::PI).unwrap() * rng.random_float();
+    let sin_theta = (T::one() - z * z).max(T::zero()).sqrt();
+
+    (center - *origin).unit().from_local(&Vec3 {
+        x: phi.cos() * sin_theta,
+        y: phi.sin() * sin_theta,
+        z,
+    })
+}
+
+/// Get the cosine of the angle between the direction from `origin` to a sphere's center and its
+/// edge, or None if `origin` is inside the sphere.
+fn cos_theta_max<T: Float>(center: Point3<T>, radius: T, origin: &Point3<T>) -> Option<T> {
+    let distance_squared = (center - *origin).length_squared();
+    let radius_squared = radius * radius;
+
+    if distance_squared <= radius_squared {
+        return None;
+    }
 
+    Some((T::one() - radius_squared / distance_squared).sqrt())
+}
+
+/// Intersect a ray with a sphere, filling in the record on a hit.  Shared by spheres that stay put
+/// and ones that move.
+pub(crate) fn hit_sphere<T: Float + Debug>(
+    center: Point3<T>,
+    radius: T,
+    material: &Arc<dyn Material<T>>,
+    r: &Ray<T>,
+    t_min: T,
+    t_max: T,
+    rec: &mut HitRecord<T>,
+) -> bool {
+    let oc = r.origin - center;
+    let a = r.direction.length_squared();
+    let half_b = oc.dot(&r.direction);
+    let c = oc.length_squared() - radius * radius;
+
+    let discriminant = half_b * half_b - a * c;
+
+    if discriminant < T::zero() {
+        return false;
+    }
+
+    let sqrtd = discriminant.sqrt();
+
+    // Find the nearest root that lies in the acceptable range.
+    let mut root = (-half_b - sqrtd) / a;
+
+    if root < t_min || t_max < root {
+        root = (-half_b + sqrtd) / a;
         if root < t_min || t_max < root {
-            root = (-half_b + sqrtd) / a;
-            if root < t_min || t_max < root {
-                return false;
-            }
+            return false;
         }
+    }
 
-        rec.t = root;
-        rec.p = r.at(rec.t);
+    rec.t = root;
+    rec.p = r.at(rec.t);
 
-        let outward_normal = (rec.p - self.center) / self.radius;
+    let outward_normal = (rec.p - center) / radius;
 
-        rec.set_face_normal(r, outward_normal);
-        rec.material = self.material.clone();
+    rec.set_face_normal(r, outward_normal);
 
-        true
-    }
+    let (u, v) = sphere_uv(&outward_normal);
+    rec.u = u;
+    rec.v = v;
+    rec.material = material.clone();
+
+    true
+}
+
+/// Get the surface coordinates of a point on the unit sphere.  u goes once around the y axis,
+/// starting and ending at -x, and v goes from 0 at the bottom to 1 at the top.
+fn sphere_uv<T: Float>(p: &Vec3<T>) -> (T, T) {
+    let pi = T::from(std::f64::consts::PI).unwrap();
+
+    let theta = (-p.y).max(-T::one()).min(T::one()).acos();
+    let phi = (-p.z).atan2(p.x) + pi;
+
+    (phi / (pi + pi), theta / pi)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn sphere_uvs() {
+    let uv = |x, y, z| sphere_uv(&Vec3 { x, y, z });
+    let close = |(u, v): (f64, f64), (eu, ev): (f64, f64)| {
+        assert!(
+            (u - eu).abs() < 1e-9 && (v - ev).abs() < 1e-9,
+            "{:?}",
+            (u, v)
+        );
+    };
+
+    close(uv(1.0, 0.0, 0.0), (0.5, 0.5));
+    close(uv(0.0, 0.0, 1.0), (0.25, 0.5));
+    close(uv(0.0, 0.0, -1.0), (0.75, 0.5));
+    assert_eq!(uv(0.0, 1.0, 0.0).1, 1.0);
+    assert_eq!(uv(0.0, -1.0, 0.0).1, 0.0);
 }
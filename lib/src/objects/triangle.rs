@@ -0,0 +1,263 @@
+////////////////
+//  TRIANGLE  //
+////////////////
+
+use crate::aabb::Aabb;
+use crate::hit::HitRecord;
+use crate::hit::Hittable;
+use crate::material::Material;
+use crate::objects::quad::area_pdf;
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::vec::{Point3, Vec3};
+use num::Float;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// A single triangle.  Without per-vertex normals it is shaded flat, and without per-vertex UVs
+/// its texture coordinates are the barycentric coordinates of the hit point.
+pub struct Triangle<T: Float> {
+    pub vertices: [Point3<T>; 3],
+    pub normals: Option<[Vec3<T>; 3]>,
+    pub uvs: Option<[(T, T); 3]>,
+    pub material: Arc<dyn Material<T>>,
+}
+
+impl<T: Float> Triangle<T> {
+    pub fn new(
+        v0: Point3<T>,
+        v1: Point3<T>,
+        v2: Point3<T>,
+        material: Arc<dyn Material<T>>,
+    ) -> Triangle<T> {
+        Triangle {
+            vertices: [v0, v1, v2],
+            normals: None,
+            uvs: None,
+            material,
+        }
+    }
+}
+
+impl<T: Float + Debug + Send + Sync> Hittable<T> for Triangle<T> {
+    fn hit(&self, r: &Ray<T>, t_min: T, t_max: T, rec: &mut HitRecord<T>, _rng: &mut Rng) -> bool {
+        match intersect(r, &self.vertices, t_min, t_max) {
+            Some((t, b1, b2)) => {
+                fill_record(
+                    r,
+                    rec,
+                    &self.vertices,
+                    self.normals.as_ref(),
+                    self.uvs.as_ref(),
+                    (t, b1, b2),
+                );
+                rec.material = self.material.clone();
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        Some(bounding_box(&self.vertices))
+    }
+
+    fn pdf_value(&self, origin: &Point3<T>, direction: &Vec3<T>) -> T {
+        let ray = Ray {
+            origin: *origin,
+            direction: *direction,
+            time: T::zero(),
+        };
+
+        match intersect(&ray, &self.vertices, T::from(0.001).unwrap(), T::infinity()) {
+            Some((t, _, _)) => {
+                let n = (self.vertices[1] - self.vertices[0])
+                    .cross(&(self.vertices[2] - self.vertices[0]));
+                let area = n.length() / T::from(2.0).unwrap();
+
+                area_pdf(direction, t, &n.unit(), area)
+            }
+            None => T::zero(),
+        }
+    }
+
+    fn random(&self, origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        let (mut b1, mut b2) = (rng.random_float::<T>(), rng.random_float::<T>());
+
+        // Fold points from the far half of the parallelogram back into the triangle.
+        if b1 + b2 > T::one() {
+            b1 = T::one() - b1;
+            b2 = T::one() - b2;
+        }
+
+        let [v0, v1, v2] = self.vertices;
+
+        v0 + (v1 - v0) * b1 + (v2 - v0) * b2 - *origin
+    }
+}
+
+/// Intersect a ray with a triangle using the Möller–Trumbore algorithm.  Returns the ray parameter
+/// and the barycentric weights of the second and third vertices.
+pub(crate) fn intersect<T: Float>(
+    r: &Ray<T>,
+    vertices: &[Point3<T>; 3],
+    t_min: T,
+    t_max: T,
+) -> Option<(T, T, T)> {
+    let edge1 = vertices[1] - vertices[0];
+    let edge2 = vertices[2] - vertices[0];
+
+    let p = r.direction.cross(&edge2);
+    let det = edge1.dot(&p);
+
+    // The ray is parallel to the triangle's plane.
+    if det.abs() < T::epsilon() {
+        return None;
+    }
+
+    let inv_det = T::one() / det;
+    let s = r.origin - vertices[0];
+
+    let b1 = s.dot(&p) * inv_det;
+    if b1 < T::zero() || b1 > T::one() {
+        return None;
+    }
+
+    let q = s.cross(&edge1);
+
+    let b2 = r.direction.dot(&q) * inv_det;
+    if b2 < T::zero() || b1 + b2 > T::one() {
+        return None;
+    }
+
+    let t = edge2.dot(&q) * inv_det;
+    if t < t_min || t_max < t {
+        return None;
+    }
+
+    Some((t, b1, b2))
+}
+
+/// Fill in a hit record from the result of `intersect`, interpolating normals and UVs if the
+/// triangle has them.
+pub(crate) fn fill_record<T: Float + Debug>(
+    r: &Ray<T>,
+    rec: &mut HitRecord<T>,
+    vertices: &[Point3<T>; 3],
+    normals: Option<&[Vec3<T>; 3]>,
+    uvs: Option<&[(T, T); 3]>,
+    (t, b1, b2): (T, T, T),
+) {
+    let b0 = T::one() - b1 - b2;
+
+    rec.t = t;
+    rec.p = r.at(t);
+
+    // Which side was hit is decided by the true surface, so that interpolated normals can't flip
+    // front_face near silhouettes.
+    let geometric_normal = (vertices[1] - vertices[0])
+        .cross(&(vertices[2] - vertices[0]))
+        .unit();
+    rec.set_face_normal(r, geometric_normal);
+
+    if let Some(n) = normals {
+        let shading_normal = (n[0] * b0 + n[1] * b1 + n[2] * b2).unit();
+
+        rec.normal = if rec.front_face {
+            shading_normal
+        } else {
+            -shading_normal
+        };
+    }
+
+    let (u, v) = match uvs {
+        Some(uv) => (
+            uv[0].0 * b0 + uv[1].0 * b1 + uv[2].0 * b2,
+            uv[0].1 * b0 + uv[1].1 * b1 + uv[2].1 * b2,
+        ),
+        None => (b1, b2),
+    };
+
+    rec.u = u;
+    rec.v = v;
+}
+
+pub(crate) fn bounding_box<T: Float>(vertices: &[Point3<T>; 3]) -> Aabb<T> {
+    let corner = Aabb::new(vertices[0], vertices[0]);
+
+    corner
+        .surrounding(&Aabb::new(vertices[1], vertices[1]))
+        .surrounding(&Aabb::new(vertices[2], vertices[2]))
+        .pad(T::from(1e-4).unwrap())
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn triangle_hit_and_barycentrics() {
+    use crate::material::Lambertian;
+    use crate::vec::Color;
+
+    let material = Arc::new(Lambertian::new(Color::one()));
+
+    let triangle = Triangle::new(
+        Point3 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        Point3 {
+            x: 1.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        Point3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        material.clone(),
+    );
+
+    let ray = Ray {
+        origin: Point3 {
+            x: 0.25,
+            y: 0.5,
+            z: 2.0,
+        },
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time: 0.0,
+    };
+
+    let mut rec = HitRecord::new(material.clone());
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+
+    assert!(triangle.hit(&ray, 0.001, f64::INFINITY, &mut rec, &mut rng));
+    assert_eq!(rec.t, 2.0);
+    assert_eq!((rec.u, rec.v), (0.25, 0.5));
+    assert!(rec.front_face);
+    assert_eq!(rec.normal.z, 1.0);
+
+    let miss = Ray {
+        origin: Point3 {
+            x: 0.75,
+            y: 0.5,
+            z: 2.0,
+        },
+        ..ray
+    };
+
+    assert!(!triangle.hit(&miss, 0.001, f64::INFINITY, &mut rec, &mut rng));
+
+    // The bounding box of a flat triangle still has some thickness.
+    let bbox = triangle.bounding_box().unwrap();
+    assert!(bbox.max.z > bbox.min.z);
+    assert!(bbox.hit(&ray, 0.001, f64::INFINITY));
+}
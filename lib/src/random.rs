@@ -1,27 +1,97 @@
 // A small collection of random number convenience functions.
 
-use crate::RNG;
 use num::traits::Float;
 
-/// Generate a random number in the range [0..1).  Generic over Floats.
-pub fn random_float<T: Float>() -> T {
-    let mut num: u64;
-    {
-        // scope controls when the RNG mutex is released
-        let mut rng = RNG.lock().unwrap();
+/// Seed used when the caller doesn't pick one.
+pub const DEFAULT_SEED: u64 = 0xda942042e4dd58b5;
 
-        *rng = rng.wrapping_mul(0xda942042e4dd58b5u64);
-        num = *rng;
+/// A PCG32 pseudo-random number generator.  Every part of the renderer that needs randomness takes
+/// one of these explicitly, so renders are repeatable and threads never share state.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+    inc: u64,
+}
+
+impl Rng {
+    /// Create a generator from a seed.  Generators created with the same seed produce the same
+    /// sequence.
+    pub fn new(seed: u64) -> Rng {
+        let mut rng = Rng {
+            state: 0,
+            inc: (0x14057b7ef767814f << 1) | 1,
+        };
+
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+
+        rng
+    }
+
+    /// Create a generator for one pixel of a render.  Seeding each pixel separately means its
+    /// samples don't depend on which thread renders it or in what order.
+    pub fn for_pixel(seed: u64, pixel_index: u64) -> Rng {
+        Rng::new(splitmix64(seed ^ splitmix64(pixel_index)))
     }
 
-    num >>= 32;
+    /// Generate a random 32-bit integer.
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(6364136223846793005).wrapping_add(self.inc);
 
-    let num = (num as f32) / 2f32.powi(32);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+
+        xorshifted.rotate_right(rot)
+    }
+
+    /// Generate a random number in the range [0..1).  Generic over Floats.
+    pub fn random_float<T: Float>(&mut self) -> T {
+        // 24 bits fit exactly in an f32, so the result can't round up to 1.
+        let num = (self.next_u32() >> 8) as f64 / 2f64.powi(24);
+
+        T::from(num).unwrap()
+    }
 
-    T::from(num).unwrap()
+    /// Generate a random number in the range [min..max) based on the given min and max values.
+    /// Generic over Floats.
+    pub fn random_float_in_range<T: Float>(&mut self, min: T, max: T) -> T {
+        min + (max - min) * self.random_float()
+    }
+}
+
+/// Scramble a 64-bit value, used to turn nearby seeds into unrelated ones.
+pub(crate) fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[test]
+fn rng_is_repeatable() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    let mut c = Rng::new(43);
+
+    let a: Vec<u32> = (0..8).map(|_| a.next_u32()).collect();
+    let b: Vec<u32> = (0..8).map(|_| b.next_u32()).collect();
+    let c: Vec<u32> = (0..8).map(|_| c.next_u32()).collect();
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
 }
 
-/// Generate a random number in the range [min..max) based on the given min and max values.  Generic over Floats.
-pub fn random_float_in_range<T: Float>(min: T, max: T) -> T {
-    min + (max - min) * random_float()
+#[test]
+fn rng_float_range() {
+    let mut rng = Rng::new(DEFAULT_SEED);
+
+    for _ in 0..10_000 {
+        let f: f64 = rng.random_float();
+        assert!((0.0..1.0).contains(&f));
+
+        let f: f32 = rng.random_float_in_range(-2.0, 3.0);
+        assert!((-2.0..3.0).contains(&f));
+    }
 }
@@ -1,13 +1,18 @@
 use num::traits::Float;
 use std::fmt::Debug;
 
-use crate::hit::{HitRecord, HittableList};
+use crate::environment::Environment;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::random::Rng;
 use crate::vec::{Color, Point3, Vec3};
 
 #[derive(Debug, Copy, Clone)]
 pub struct Ray<T: Float> {
     pub origin: Point3<T>,
     pub direction: Vec3<T>,
+    /// When the ray was cast, for scenes with moving objects.  The camera's shutter is open over
+    /// some part of the interval [0, 1].
+    pub time: T,
 }
 
 #[allow(dead_code)]
@@ -17,6 +22,7 @@ impl<T: Float + Debug> Ray<T> {
         Ray {
             origin: Point3::zero(),
             direction: Vec3::zero(),
+            time: T::zero(),
         }
     }
 
@@ -24,55 +30,219 @@ impl<T: Float + Debug> Ray<T> {
     pub fn at(&self, t: T) -> Vec3<T> {
         self.origin + self.direction * t
     }
+}
 
-    pub fn color(&self, rec: &mut HitRecord<T>, world: &HittableList<T>, depth: i32) -> Color<T> {
-        rec.ray_count += 1;
+impl<T: Float + Debug + Send + Sync> Ray<T> {
+    /// Trace the ray through the world and get the light arriving along it.  Rays that escape see
+    /// the environment.  Unless they're specular, surfaces aim a ray at `lights` or the bright parts
+    /// of the environment as well as scattering one, and the two are combined with multiple
+    /// importance sampling.  `lights` may be empty.
+    ///
+    /// After a few bounces, paths carrying little light are ended at random with Russian roulette,
+    /// and the ones that survive are brightened to make up for it.  `depth` is only a safety net
+    /// for paths that never get dim, like ones between mirrors.
+    pub fn color(
+        &self,
+        rec: &mut HitRecord<T>,
+        world: &dyn Hittable<T>,
+        lights: &HittableList<T>,
+        environment: &Environment<T>,
+        depth: i32,
+        rng: &mut Rng,
+    ) -> Color<T> {
+        let sampler = LightSampler {
+            lights,
+            environment,
+        };
 
-        if depth <= 0 {
-            return Color::zero();
+        let mut ray = *self;
+        // The light each bounce finds itself, and the weight it gives the light found after it.
+        // These are added up back to front once the path ends, so the sums come out exactly as
+        // they would tracing the path recursively.
+        let mut found: Vec<(Color<T>, Color<T>)> = Vec::new();
+        // The light found where the path ends.
+        let mut radiance = Color::zero();
+        // The fraction of light found from here on that makes it back to the camera.
+        let mut throughput = Color::one();
+        // The density with which the last bounce picked the ray's direction, or None for camera
+        // rays and specular bounces.  Light the ray finds is weighted by how likely aiming at the
+        // lights was to find it instead.
+        let mut scatter_pdf = None;
+
+        for bounces in 0..depth {
+            rec.ray_count += 1;
+
+            if !world.hit(&ray, T::from(0.001).unwrap(), T::infinity(), rec, rng) {
+                let background = environment.color(&ray.direction);
+                radiance = background * sampler.scatter_weight(&ray, scatter_pdf);
+                break;
+            }
+
+            let mut emitted = rec.material.emitted(rec);
+
+            if emitted != Color::zero() {
+                emitted = emitted * sampler.scatter_weight(&ray, scatter_pdf);
+            }
+
+            let scatter = match rec.material.scatter(&ray, rec, rng) {
+                Some(scatter) => scatter,
+                None => {
+                    radiance = emitted;
+                    break;
+                }
+            };
+
+            // The record is reused by the shadow ray and the next bounce, so this comes last.
+            let direct = match scatter.pdf {
+                Some(_) if !sampler.is_empty() => sampler.direct_light(&ray, rec, world, rng),
+                _ => Color::zero(),
+            };
+
+            let mut weight = scatter.weight();
+
+            if bounces >= ROULETTE_MIN_BOUNCES {
+                let survival = (throughput * weight)
+                    .max_component()
+                    .min(T::from(ROULETTE_MAX_SURVIVAL).unwrap());
+
+                if rng.random_float::<T>() >= survival {
+                    rec.terminated_paths += 1;
+                    radiance = emitted + direct;
+                    break;
+                }
+
+                weight = weight / survival;
+            }
+
+            found.push((emitted + direct, weight));
+            throughput = throughput * weight;
+            scatter_pdf = scatter.pdf;
+            ray = scatter.ray;
         }
 
-        if world.hit(self, T::from(0.001).unwrap(), T::infinity(), rec) {
-            let mut scattered = Ray::<T>::new();
-            let mut attenuation = Color::<T>::zero();
+        found
+            .iter()
+            .rev()
+            .fold(radiance, |after, &(light, weight)| light + weight * after)
+    }
+}
+
+/// Bounces a path always gets before Russian roulette may end it.
+const ROULETTE_MIN_BOUNCES: i32 = 3;
 
-            let is_scattered = rec
-                .material
-                .scatter(self, &*rec, &mut attenuation, &mut scattered);
+/// Even bright paths are ended now and then, so light trapped between mirrors doesn't bounce until
+/// it runs out of depth.
+const ROULETTE_MAX_SURVIVAL: f64 = 0.95;
+
+/// Picks directions toward the scene's lights and the bright parts of its environment, choosing
+/// between the two evenly when there are both.
+struct LightSampler<'a, T: Float> {
+    lights: &'a HittableList<T>,
+    environment: &'a Environment<T>,
+}
 
-            if is_scattered {
-                return attenuation * scattered.color(rec, world, depth - 1);
-            } else {
-                return Color::<T>::zero();
+impl<'a, T: Float + Debug + Send + Sync> LightSampler<'a, T> {
+    fn is_empty(&self) -> bool {
+        self.lights.is_empty() && !self.environment.is_sampled()
+    }
+
+    /// Pick a unit direction from `origin`.
+    fn sample(&self, origin: &Point3<T>, rng: &mut Rng) -> Vec3<T> {
+        let use_lights = match (self.lights.is_empty(), self.environment.is_sampled()) {
+            (false, true) => rng.random_float::<T>() < T::from(0.5).unwrap(),
+            (lights_empty, _) => !lights_empty,
+        };
+
+        if use_lights {
+            self.lights.random(origin, rng).unit()
+        } else {
+            self.environment.sample(rng).unit()
+        }
+    }
+
+    /// Get the probability density, per unit solid angle, of `sample` picking a unit direction.
+    fn pdf(&self, origin: &Point3<T>, direction: &Vec3<T>) -> T {
+        let lights = || self.lights.pdf_value(origin, direction);
+        let environment = || self.environment.pdf(direction);
+
+        match (self.lights.is_empty(), self.environment.is_sampled()) {
+            (false, true) => (lights() + environment()) * T::from(0.5).unwrap(),
+            (false, false) => lights(),
+            (true, true) => environment(),
+            (true, false) => T::zero(),
+        }
+    }
+
+    /// Get the weight for light found by a scattered ray, given the density its direction was
+    /// picked with.
+    fn scatter_weight(&self, ray: &Ray<T>, scatter_pdf: Option<T>) -> T {
+        match scatter_pdf {
+            Some(scatter_pdf) if !self.is_empty() => {
+                let light_pdf = self.pdf(&ray.origin, &ray.direction.unit());
+
+                power_heuristic(scatter_pdf, light_pdf)
             }
+            _ => T::one(),
+        }
+    }
 
-            // let target = diffuse_renderer(rec.p.clone(), rec.normal.clone());
+    /// Get the light reflected back along `r_in` from a shadow ray aimed at the lights or the
+    /// environment, weighted against scattering having found it.  The shadow ray is counted in the
+    /// record.
+    fn direct_light(
+        &self,
+        r_in: &Ray<T>,
+        rec: &mut HitRecord<T>,
+        world: &dyn Hittable<T>,
+        rng: &mut Rng,
+    ) -> Color<T> {
+        let direction = self.sample(&rec.p, rng);
+        let light_pdf = self.pdf(&rec.p, &direction);
 
-            // let new_ray = Ray {
-            //     origin: rec.p.clone(),
-            //     direction: target - rec.p.clone(),
-            // };
-            // return ray_color(&new_ray, rec, world, depth - 1, diffuse_renderer)
-            //     * T::from(0.5).unwrap();
+        if light_pdf <= T::zero() {
+            return Color::zero();
         }
 
-        let unit_direction = self.direction.unit();
+        let bsdf = rec.material.eval(r_in, rec, &direction);
 
-        let t = T::from(0.5).unwrap() * (unit_direction.y + T::from(1.0).unwrap());
+        // Facing away from the surface, or otherwise not reflected.
+        if bsdf == Color::zero() {
+            return Color::zero();
+        }
 
-        Vec3 {
-            x: T::from(248.0 / 255.0).unwrap(),
-            y: T::from(245.0 / 255.0).unwrap(),
-            z: T::from(254.0 / 255.0).unwrap(),
-        } * (T::one() - t)
-            + Vec3 {
-                x: T::from(139.0 / 255.0).unwrap(),
-                y: T::from(179.0 / 255.0).unwrap(),
-                z: T::from(237.0 / 255.0).unwrap(),
-            } * t
+        let scatter_pdf = rec.material.scattering_pdf(r_in, rec, &direction);
+        let shadow = Ray {
+            origin: rec.p,
+            direction,
+            time: r_in.time,
+        };
+
+        rec.ray_count += 1;
+
+        // Whatever the shadow ray finds first, whether a light, something blocking it or the
+        // environment, gives off the light that arrives.
+        let arriving = if world.hit(&shadow, T::from(0.001).unwrap(), T::infinity(), rec, rng) {
+            rec.material.emitted(rec)
+        } else {
+            self.environment.color(&direction)
+        };
+
+        bsdf * arriving * (power_heuristic(light_pdf, scatter_pdf) / light_pdf)
     }
 }
 
+/// Weight a sample from a strategy with density `pdf` against another strategy with density
+/// `other_pdf`, using Veach's power heuristic with an exponent of two.
+fn power_heuristic<T: Float>(pdf: T, other_pdf: T) -> T {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+
+    if a + b <= T::zero() {
+        return T::zero();
+    }
+
+    a / (a + b)
+}
+
 impl<T: Float + Debug> Default for Ray<T> {
     fn default() -> Self {
         Self::new()
@@ -92,6 +262,7 @@ fn ray_at() {
             y: -2.0,
             z: 1.0,
         },
+        time: 0.0,
     };
 
     let expected = Vec3 {
@@ -102,3 +273,309 @@ fn ray_at() {
 
     assert_eq!(ray.at(10.0), expected);
 }
+
+#[test]
+fn ray_color_with_light_and_environment() {
+    use crate::material::{DiffuseLight, Lambertian};
+    use crate::objects::sphere::Sphere;
+    use std::sync::Arc;
+
+    let emit = Color {
+        x: 4.0,
+        y: 3.0,
+        z: 2.0,
+    };
+
+    let light = Sphere {
+        center: Point3 {
+            x: 0.0,
+            y: 0.0,
+            z: -2.0,
+        },
+        radius: 0.5,
+        material: Arc::new(DiffuseLight { emit }),
+    };
+
+    let toward = Ray {
+        origin: Point3::zero(),
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+        time: 0.0,
+    };
+
+    let away = Ray {
+        direction: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+        },
+        ..toward
+    };
+
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+    let mut rec = HitRecord::new(Arc::new(Lambertian::new(Color::one())));
+
+    let black = Environment::None;
+    let sky = Environment::sky();
+    let no_lights = HittableList::new(Vec::new());
+
+    assert_eq!(
+        toward.color(&mut rec, &light, &no_lights, &black, 5, &mut rng),
+        emit
+    );
+    assert_eq!(
+        away.color(&mut rec, &light, &no_lights, &black, 5, &mut rng),
+        Color::zero()
+    );
+    assert_ne!(
+        away.color(&mut rec, &light, &no_lights, &sky, 5, &mut rng),
+        Color::zero()
+    );
+}
+
+#[test]
+fn environment_map_sampling_is_unbiased() {
+    use crate::environment::EnvironmentMap;
+    use crate::material::Lambertian;
+    use crate::objects::sphere::Sphere;
+    use std::f64::consts::PI;
+    use std::sync::Arc;
+
+    // A dim map with one bright pixel above the horizon.
+    let (width, height) = (16, 8);
+    let mut pixels = vec![Color::one() * 0.1; width * height];
+    pixels[2 * width + 5] = Color {
+        x: 500.0,
+        y: 400.0,
+        z: 300.0,
+    };
+
+    let map = Arc::new(EnvironmentMap::new(width, height, pixels, 1.0, 0.0));
+
+    // The expected light reflected by an upward-facing white floor is the cosine-weighted average
+    // of the map over the upper hemisphere.
+    let (columns, rows) = (512, 128);
+    let mut expected = Color::zero();
+
+    for row in 0..rows {
+        let theta = (row as f64 + 0.5) * PI / 2.0 / rows as f64;
+
+        for column in 0..columns {
+            let phi = (column as f64 + 0.5) * 2.0 * PI / columns as f64;
+            let direction = Vec3 {
+                x: theta.sin() * phi.cos(),
+                y: theta.cos(),
+                z: theta.sin() * phi.sin(),
+            };
+
+            let solid_angle = theta.sin() * (PI / 2.0 / rows as f64) * (2.0 * PI / columns as f64);
+            expected += map.color(&direction) * (theta.cos() / PI * solid_angle);
+        }
+    }
+
+    let floor = Sphere {
+        center: Point3 {
+            x: 0.0,
+            y: -1000.0,
+            z: 0.0,
+        },
+        radius: 1000.0,
+        material: Arc::new(Lambertian::new(Color::one())),
+    };
+
+    let down = Ray {
+        origin: Point3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        direction: Vec3 {
+            x: 0.0,
+            y: -1.0,
+            z: 0.0,
+        },
+        time: 0.0,
+    };
+
+    let environment = Environment::Map(map);
+    let no_lights = HittableList::new(Vec::new());
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+    let mut rec = HitRecord::new(floor.material.clone());
+
+    let samples = 20_000;
+    let mut total = Color::zero();
+
+    for _ in 0..samples {
+        total += down.color(&mut rec, &floor, &no_lights, &environment, 2, &mut rng);
+    }
+
+    let average = total / samples as f64;
+
+    assert!(
+        (average.x / expected.x - 1.0).abs() < 0.03,
+        "expected {:?} but got {:?}",
+        expected,
+        average
+    );
+}
+
+#[test]
+fn direct_light_sampling_matches_analytic_lighting() {
+    use crate::material::{DiffuseLight, Lambertian, Material};
+    use crate::objects::quad::Quad;
+    use crate::objects::sphere::Sphere;
+    use std::f64::consts::PI;
+    use std::sync::Arc;
+
+    let white: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(Color::one()));
+    let glow = |emit: f64| -> Arc<dyn Material<f64>> {
+        Arc::new(DiffuseLight {
+            emit: Color::one() * emit,
+        })
+    };
+    let point = |x, y, z| Point3 { x, y, z };
+
+    let floor = || -> Box<dyn Hittable<f64>> {
+        Box::new(Quad::new(
+            point(-100.0, 0.0, 100.0),
+            Vec3 {
+                x: 200.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vec3 {
+                x: 0.0,
+                y: 0.0,
+                z: -200.0,
+            },
+            white.clone(),
+        ))
+    };
+
+    // A white floor lit from 5 units up.  A sphere light seen across an angle α gives off
+    // emit * sin²(α) from the floor, and a tiny square one emit * area / (π * distance²).
+    let sphere: Arc<dyn Hittable<f64>> = Arc::new(Sphere {
+        center: point(0.0, 5.0, 0.0),
+        radius: 0.5,
+        material: glow(100.0),
+    });
+    let square: Arc<dyn Hittable<f64>> = Arc::new(Quad::new(
+        point(-0.1, 5.0, -0.1),
+        Vec3 {
+            x: 0.2,
+            y: 0.0,
+            z: 0.0,
+        },
+        Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.2,
+        },
+        glow(1000.0),
+    ));
+
+    let cases = [
+        (sphere, 100.0 * 0.01),
+        (square, 1000.0 * 0.04 / (PI * 25.0)),
+    ];
+
+    let down = Ray {
+        origin: point(0.0, 1.0, 0.0),
+        direction: Vec3 {
+            x: 0.0,
+            y: -1.0,
+            z: 0.0,
+        },
+        time: 0.0,
+    };
+
+    for (light, expected) in cases {
+        let world = HittableList::new(vec![floor(), Box::new(light.clone())]);
+        let lights = HittableList::new(vec![Box::new(light)]);
+
+        let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+        let mut rec = HitRecord::new(white.clone());
+        let samples = 2_000;
+        let mut total = Color::zero();
+
+        // With a depth of 2, only light arriving straight from the lights is counted.
+        for _ in 0..samples {
+            total += down.color(&mut rec, &world, &lights, &Environment::None, 2, &mut rng);
+        }
+
+        let average = total.x / samples as f64;
+        assert!(
+            (average / expected - 1.0).abs() < 0.02,
+            "expected {} but got {}",
+            expected,
+            average
+        );
+    }
+}
+
+#[test]
+fn russian_roulette_keeps_interreflections_unbiased() {
+    use crate::material::{DiffuseLight, Lambertian, Material};
+    use crate::objects::sphere::Sphere;
+    use std::sync::Arc;
+
+    // Inside a closed sphere with walls of albedo ρ around a small light, every bounce sees the
+    // same light again, so the walls are ρ / (1 - ρ) times as bright as from the light alone.
+    // Here that's 1, less about 1% for light the source blocks between walls.
+    let grey: Arc<dyn Material<f64>> = Arc::new(Lambertian::new(Color::one() * 0.5));
+    let light: Arc<dyn Hittable<f64>> = Arc::new(Sphere {
+        center: Point3::zero(),
+        radius: 0.1,
+        material: Arc::new(DiffuseLight {
+            emit: Color::one() * 100.0,
+        }),
+    });
+    let walls = Sphere {
+        center: Point3::zero(),
+        radius: 1.0,
+        material: grey.clone(),
+    };
+
+    let world = HittableList::new(vec![Box::new(walls), Box::new(light.clone())]);
+    let lights = HittableList::new(vec![Box::new(light)]);
+
+    let up = Ray {
+        origin: Point3 {
+            x: 0.0,
+            y: 0.5,
+            z: 0.0,
+        },
+        direction: Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        time: 0.0,
+    };
+
+    let mut rng = Rng::new(crate::random::DEFAULT_SEED);
+    let mut rec = HitRecord::new(grey);
+    let samples = 4_000;
+    let mut total = Color::zero();
+
+    for _ in 0..samples {
+        total += up.color(
+            &mut rec,
+            &world,
+            &lights,
+            &Environment::None,
+            1000,
+            &mut rng,
+        );
+    }
+
+    let average = total.x / samples as f64;
+    assert!((average - 0.99).abs() < 0.03, "got {}", average);
+
+    // Nearly every path is ended by roulette long before the depth limit.
+    assert!(rec.terminated_paths > samples * 9 / 10);
+    assert!(rec.ray_count < samples * 30);
+}
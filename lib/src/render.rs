@@ -0,0 +1,310 @@
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             RENDER                                             //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+use crate::camera::Camera;
+use crate::environment::Environment;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::integrator::{Integrator, IntegratorKind};
+use crate::material::{Lambertian, Material};
+use crate::random::{Rng, DEFAULT_SEED};
+use crate::vec::Color;
+use crate::write::FinalImage;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+/// Everything about a render that isn't the scene or the camera.
+#[derive(Debug, Copy, Clone)]
+pub struct RenderSettings {
+    pub width: u32,
+    pub height: u32,
+    pub samples_per_pixel: i32,
+    pub max_depth: i32,
+    /// Renders with the same seed and settings produce identical images.
+    pub seed: u64,
+    /// Number of worker threads.  With one thread, rendering happens on the calling thread, which
+    /// is what WebAssembly needs.
+    pub threads: usize,
+    /// How to color each sample.  Anything but the path tracer is for debugging scenes.
+    pub integrator: IntegratorKind,
+}
+
+impl RenderSettings {
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            width: 500,
+            height: 333,
+            samples_per_pixel: 10,
+            max_depth: 50,
+            seed: DEFAULT_SEED,
+            threads: 1,
+            integrator: IntegratorKind::Path,
+        }
+    }
+}
+
+/// Renders a scene through a camera into a FinalImage.
+pub struct Renderer<'a> {
+    world: &'a dyn Hittable<f64>,
+    /// Objects that give off light, which diffuse surfaces sample directly.
+    lights: &'a HittableList<f64>,
+    environment: &'a Environment<f64>,
+    camera: &'a Camera<f64>,
+    settings: RenderSettings,
+    integrator: Box<dyn Integrator<f64>>,
+    // Default material to clone into each HitRecord
+    default_material: Arc<dyn Material<f64>>,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(
+        world: &'a dyn Hittable<f64>,
+        lights: &'a HittableList<f64>,
+        environment: &'a Environment<f64>,
+        camera: &'a Camera<f64>,
+        settings: RenderSettings,
+    ) -> Renderer<'a> {
+        Renderer {
+            world,
+            lights,
+            environment,
+            camera,
+            settings,
+            integrator: settings.integrator.build(settings.max_depth),
+            default_material: Arc::new(Lambertian::new(Color {
+                x: 122.0 / 255.0,
+                y: 175.0 / 255.0,
+                z: 238.0 / 255.0,
+            })),
+        }
+    }
+
+    pub fn settings(&self) -> &RenderSettings {
+        &self.settings
+    }
+
+    /// Render the image.
+    pub fn render(&self) -> FinalImage {
+        self.render_with_progress(|| {})
+    }
+
+    /// Render the image, calling `on_row` on the calling thread each time a row is finished.
+    pub fn render_with_progress(&self, mut on_row: impl FnMut()) -> FinalImage {
+        let width = self.settings.width as usize;
+        let height = self.settings.height as usize;
+
+        let mut pixels = vec![Color::zero(); width * height];
+        let mut total_rays: u64 = 0;
+        let mut terminated_paths: u64 = 0;
+
+        if self.settings.threads <= 1 {
+            for row in 0..height {
+                let (row_pixels, row_rays, row_terminated) = self.render_row(row);
+                pixels[row * width..(row + 1) * width].copy_from_slice(&row_pixels);
+                total_rays += row_rays;
+                terminated_paths += row_terminated;
+                on_row();
+            }
+        } else {
+            // Workers claim rows from a shared counter and send each finished row back to this
+            // thread, which copies it into place.
+            let next_row = AtomicUsize::new(0);
+            let (tx, rx) = mpsc::channel();
+
+            thread::scope(|s| {
+                for _ in 0..self.settings.threads {
+                    let tx = tx.clone();
+                    let next_row = &next_row;
+
+                    s.spawn(move || loop {
+                        let row = next_row.fetch_add(1, Ordering::Relaxed);
+
+                        if row >= height {
+                            break;
+                        }
+
+                        let (row_pixels, row_rays, row_terminated) = self.render_row(row);
+                        tx.send((row, row_pixels, row_rays, row_terminated))
+                            .unwrap();
+                    });
+                }
+
+                // Only the workers' senders remain, so the loop below ends when the last worker
+                // does.
+                drop(tx);
+
+                for (row, row_pixels, row_rays, row_terminated) in rx {
+                    pixels[row * width..(row + 1) * width].copy_from_slice(&row_pixels);
+                    total_rays += row_rays;
+                    terminated_paths += row_terminated;
+                    on_row();
+                }
+            });
+        }
+
+        FinalImage {
+            pixels,
+            width: self.settings.width,
+            height: self.settings.height,
+            samples_per_pixel: self.settings.samples_per_pixel,
+            total_rays,
+            terminated_paths,
+        }
+    }
+
+    /// Render one row of the image, counting from the top.  Returns the row's pixels, the number of
+    /// rays cast and the number of paths ended by Russian roulette.
+    fn render_row(&self, row: usize) -> (Vec<Color<f64>>, u64, u64) {
+        let width = self.settings.width as f64;
+        let height = self.settings.height as f64;
+        let samples_per_pixel = self.settings.samples_per_pixel;
+
+        // Rows are stored top to bottom, but v runs bottom to top.
+        let y = self.settings.height as usize - 1 - row;
+
+        let mut row_pixels = Vec::with_capacity(self.settings.width as usize);
+        let mut row_rays: u64 = 0;
+        let mut row_terminated: u64 = 0;
+
+        for x in 0..self.settings.width as usize {
+            let mut p = Color::zero();
+
+            // Each pixel gets its own generator, so the image depends only on the seed and not on
+            // how rows were divided between threads.
+            let pixel_index = (row * self.settings.width as usize + x) as u64;
+            let mut rng = Rng::for_pixel(self.settings.seed, pixel_index);
+
+            for _ in 0..samples_per_pixel {
+                // don't use RNG if there's only one sample per pixel
+                let u_rand = if samples_per_pixel > 1 {
+                    rng.random_float()
+                } else {
+                    1.0
+                };
+
+                let v_rand = if samples_per_pixel > 1 {
+                    rng.random_float()
+                } else {
+                    1.0
+                };
+
+                let u = (u_rand + x as f64) / (width - 1.0);
+                let v = (v_rand + y as f64) / (height - 1.0);
+
+                let ray = self.camera.get_ray(u, v, &mut rng);
+
+                let mut rec = HitRecord::new(self.default_material.clone());
+
+                p += self.integrator.radiance(
+                    &ray,
+                    self.world,
+                    self.lights,
+                    self.environment,
+                    &mut rec,
+                    &mut rng,
+                );
+
+                row_rays += rec.ray_count;
+                row_terminated += rec.terminated_paths;
+            }
+
+            row_pixels.push(p);
+        }
+
+        (row_pixels, row_rays, row_terminated)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn render_is_independent_of_thread_count() {
+    use crate::scenes::three_sphere_scene;
+
+    let world = three_sphere_scene::scene();
+    let lights = HittableList::new(Vec::new());
+    let environment = Environment::sky();
+
+    let settings = RenderSettings {
+        width: 40,
+        height: 30,
+        samples_per_pixel: 4,
+        ..Default::default()
+    };
+
+    let camera = three_sphere_scene::camera().build(settings.aspect_ratio());
+
+    let single = Renderer::new(&world, &lights, &environment, &camera, settings).render();
+    let multi = Renderer::new(
+        &world,
+        &lights,
+        &environment,
+        &camera,
+        RenderSettings {
+            threads: 3,
+            ..settings
+        },
+    )
+    .render();
+
+    assert_eq!(single.pixels, multi.pixels);
+    assert_eq!(single.total_rays, multi.total_rays);
+    assert_eq!(single.terminated_paths, multi.terminated_paths);
+    assert_eq!(single.pixels.len(), 40 * 30);
+}
+
+#[test]
+fn render_output_is_unchanged() {
+    use crate::scenes;
+
+    // Output and counts from before tracing became a loop.  Update these only for changes that
+    // are meant to alter images.
+    let pinned = [
+        ("cornell_box", 0x42b3_6c4b_870a_34d8u64, 5888, 395),
+        ("three_sphere_scene", 0xa3f3_b478_b456_fd2e, 3393, 59),
+        ("glass_sphere_scene", 0x3a23_b71e_8141_c44f, 4291, 188),
+    ];
+
+    let settings = RenderSettings {
+        width: 24,
+        height: 16,
+        samples_per_pixel: 4,
+        ..Default::default()
+    };
+
+    for (name, hash, total_rays, terminated_paths) in pinned {
+        let scene = scenes::by_name(name).unwrap();
+        let camera = scene.camera.build(settings.aspect_ratio());
+        let image = Renderer::new(
+            &scene.world,
+            &scene.lights,
+            &scene.environment,
+            &camera,
+            settings,
+        )
+        .render();
+
+        // FNV-1a over the bits of the summed samples, a word at a time, so even rounding
+        // differences show up.
+        let image_hash = image
+            .pixels
+            .iter()
+            .flat_map(|pixel| [pixel.x, pixel.y, pixel.z])
+            .fold(0xcbf2_9ce4_8422_2325, |hash, sum: f64| {
+                (hash ^ sum.to_bits()).wrapping_mul(0x0100_0000_01b3)
+            });
+
+        assert_eq!(image_hash, hash, "{}", name);
+        assert_eq!(image.total_rays, total_rays, "{}", name);
+        assert_eq!(image.terminated_paths, terminated_paths, "{}", name);
+    }
+}
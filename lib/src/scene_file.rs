@@ -0,0 +1,1211 @@
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                           SCENE FILES                                          //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+//! Scenes described in JSON instead of Rust.  A scene file lists named materials, the objects that
+//! use them, the camera, and optionally render settings:
+//!
+//! ```json
+//! {
+//!   "render": { "width": 400, "samples_per_pixel": 50 },
+//!   "camera": {
+//!     "lookfrom": [13.0, 2.0, 3.0],
+//!     "lookat": [0.0, 0.0, 0.0],
+//!     "vfov": 20.0
+//!   },
+//!   "environment": { "type": "gradient", "bottom": [1.0, 1.0, 1.0], "top": [0.5, 0.7, 1.0] },
+//!   "materials": {
+//!     "ground": { "type": "lambertian", "albedo": [0.5, 0.5, 0.5] },
+//!     "glass": { "type": "dielectric", "ir": 1.5 }
+//!   },
+//!   "objects": [
+//!     { "type": "sphere", "center": [0.0, -1000.0, 0.0], "radius": 1000.0, "material": "ground" },
+//!     { "type": "sphere", "center": [0.0, 1.0, 0.0], "radius": 1.0, "material": "glass" }
+//!   ]
+//! }
+//! ```
+
+use crate::bvh::FlatBvh;
+use crate::camera::CameraSettings;
+use crate::environment::{self, DaylightSky, Environment, EnvironmentMap};
+use crate::hit::{Hittable, HittableList};
+use crate::integrator::{self, IntegratorKind};
+use crate::material::{Dielectric, DiffuseLight, Isotropic, Lambertian, Material, Metal};
+use crate::obj::{ObjError, ObjModel};
+use crate::objects::box_shape::BoxShape;
+use crate::objects::constant_medium::ConstantMedium;
+use crate::objects::instance::Instance;
+use crate::objects::moving_sphere::MovingSphere;
+use crate::objects::quad::Quad;
+use crate::objects::sphere::Sphere;
+use crate::objects::triangle::Triangle;
+use crate::random::Rng;
+use crate::render::RenderSettings;
+use crate::scenes::Scene;
+use crate::texture::{
+    Checker, Clouds, Filter, ImageTexture, Marble, SolidColor, Texture, Wood, Wrap,
+};
+use crate::transform::Pose;
+use crate::vec::Vec3;
+use image::ImageError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The contents of a scene file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneFile {
+    #[serde(default)]
+    pub render: RenderDescription,
+    pub camera: CameraDescription,
+    #[serde(default)]
+    pub environment: EnvironmentDescription,
+    pub materials: BTreeMap<String, MaterialDescription>,
+    pub objects: Vec<ObjectDescription>,
+}
+
+/// Render settings from a scene file.  Anything left out falls back to the renderer's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RenderDescription {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub samples_per_pixel: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrator: Option<IntegratorDescription>,
+}
+
+/// How to color each sample.  Anything but `path` is for debugging the scene.  Settings must be
+/// greater than zero.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum IntegratorDescription {
+    Path,
+    Normals,
+    Depth {
+        #[serde(default = "default_max_distance")]
+        max_distance: f64,
+    },
+    Albedo,
+    AmbientOcclusion {
+        #[serde(default = "default_ao_radius")]
+        radius: f64,
+    },
+    BounceHeatmap {
+        #[serde(default = "default_max_rays")]
+        max_rays: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraDescription {
+    pub lookfrom: [f64; 3],
+    pub lookat: [f64; 3],
+    #[serde(default = "default_vup")]
+    pub vup: [f64; 3],
+    /// Vertical field of view, in degrees.
+    pub vfov: f64,
+    #[serde(default)]
+    pub aperture: f64,
+    #[serde(default = "default_focus_dist")]
+    pub focus_dist: f64,
+    /// Shutter open and close times, within [0, 1].  Moving objects blur over this interval.
+    #[serde(default)]
+    pub shutter_open: f64,
+    #[serde(default)]
+    pub shutter_close: f64,
+}
+
+/// What rays see when they leave the scene.  Defaults to the built-in scenes' sky.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum EnvironmentDescription {
+    #[default]
+    Sky,
+    None,
+    Solid {
+        color: [f64; 3],
+    },
+    Gradient {
+        bottom: [f64; 3],
+        top: [f64; 3],
+    },
+    /// An equirectangular Radiance `.hdr` image.  Relative paths are relative to the scene file.
+    Map {
+        path: PathBuf,
+        #[serde(default = "default_intensity")]
+        intensity: f64,
+        /// Rotation about the y axis, in degrees.
+        #[serde(default)]
+        rotation: f64,
+    },
+    /// A physically based clear sky.  Angles are in degrees, with azimuth measured from -z toward
+    /// +x.  `sun_size` is the sun's angular radius.
+    Daylight {
+        sun_elevation: f64,
+        #[serde(default)]
+        sun_azimuth: f64,
+        #[serde(default = "default_turbidity")]
+        turbidity: f64,
+        #[serde(default = "default_sun_size")]
+        sun_size: f64,
+        #[serde(default = "default_intensity")]
+        intensity: f64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MaterialDescription {
+    Lambertian {
+        albedo: TextureDescription,
+    },
+    Metal {
+        albedo: TextureDescription,
+        #[serde(default)]
+        fuzz: f64,
+    },
+    Dielectric {
+        ir: f64,
+        #[serde(default = "default_albedo")]
+        albedo: TextureDescription,
+    },
+    DiffuseLight {
+        emit: [f64; 3],
+    },
+    /// Scatters light in every direction, for filling a `constant_medium`.
+    Isotropic {
+        albedo: TextureDescription,
+    },
+}
+
+/// A material's color, given either as r,g,b or as a texture object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TextureDescription {
+    /// A 3D checkerboard of cubes with sides `scale` long.
+    Checker {
+        scale: f64,
+        even: Box<TextureDescription>,
+        odd: Box<TextureDescription>,
+    },
+    /// A PNG or JPEG image in sRGB.  Relative paths are relative to the scene file.
+    Image {
+        path: PathBuf,
+        #[serde(default)]
+        wrap: Wrap,
+        #[serde(default)]
+        filter: Filter,
+    },
+    /// Noise-warped bands running along z.  The seed picks the noise pattern.
+    Marble {
+        scale: f64,
+        base: [f64; 3],
+        vein: [f64; 3],
+        #[serde(default)]
+        seed: u64,
+    },
+    /// Growth rings around the y axis.
+    Wood {
+        scale: f64,
+        light: [f64; 3],
+        dark: [f64; 3],
+        #[serde(default)]
+        seed: u64,
+    },
+    /// Patches of cloud covering roughly `coverage` of the surface, from 0 to 1.
+    Clouds {
+        scale: f64,
+        #[serde(default = "default_coverage")]
+        coverage: f64,
+        sky: [f64; 3],
+        cloud: [f64; 3],
+        #[serde(default)]
+        seed: u64,
+    },
+    #[serde(untagged)]
+    Color([f64; 3]),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ObjectDescription {
+    Sphere {
+        center: [f64; 3],
+        radius: f64,
+        /// Name of an entry in the scene's materials.
+        material: String,
+    },
+    /// A sphere moving in a straight line from `center0` at time 0 to `center1` at time 1.
+    MovingSphere {
+        center0: [f64; 3],
+        center1: [f64; 3],
+        radius: f64,
+        material: String,
+    },
+    Triangle {
+        vertices: [[f64; 3]; 3],
+        material: String,
+    },
+    /// A parallelogram with corner `q` and sides `u` and `v`, facing along u × v.
+    Quad {
+        q: [f64; 3],
+        u: [f64; 3],
+        v: [f64; 3],
+        material: String,
+    },
+    /// An axis-aligned box between two opposite corners.
+    Box {
+        min: [f64; 3],
+        max: [f64; 3],
+        material: String,
+    },
+    /// A Wavefront OBJ model, which brings its own materials.  Relative paths are relative to the
+    /// scene file, and a model used more than once is only loaded once.
+    Obj { path: PathBuf },
+    /// Fog or smoke filling another object, which must be closed and convex.  Density is the
+    /// chance of scattering per unit of distance and must be greater than zero.  The material is
+    /// usually `isotropic`.
+    ConstantMedium {
+        boundary: Box<ObjectDescription>,
+        density: f64,
+        material: String,
+    },
+    /// Another object scaled, then rotated about x, y and z in turn by degrees, then translated.
+    /// With `end`, the instance moves from this pose at time 0 to that one at time 1.  Scale factors
+    /// can't be zero, or change sign between the two poses.
+    Instance {
+        object: Box<ObjectDescription>,
+        #[serde(default = "default_scale")]
+        scale: [f64; 3],
+        #[serde(default)]
+        rotate: [f64; 3],
+        #[serde(default)]
+        translate: [f64; 3],
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        end: Option<PoseDescription>,
+    },
+}
+
+/// Where an animated instance is at time 1.  Anything left out is the same as at time 0.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoseDescription {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<[f64; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotate: Option<[f64; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translate: Option<[f64; 3]>,
+}
+
+/// Return an `Invalid` error with `message` unless `ok`.
+fn check(ok: bool, message: &str) -> Result<(), SceneFileError> {
+    if ok {
+        Ok(())
+    } else {
+        Err(SceneFileError::Invalid(message.to_string()))
+    }
+}
+
+fn default_vup() -> [f64; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_scale() -> [f64; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn default_focus_dist() -> f64 {
+    10.0
+}
+
+fn default_max_distance() -> f64 {
+    integrator::DEFAULT_MAX_DISTANCE
+}
+
+fn default_ao_radius() -> f64 {
+    integrator::DEFAULT_AO_RADIUS
+}
+
+fn default_max_rays() -> u32 {
+    integrator::DEFAULT_MAX_RAYS
+}
+
+fn default_intensity() -> f64 {
+    1.0
+}
+
+fn default_turbidity() -> f64 {
+    environment::DEFAULT_TURBIDITY
+}
+
+fn default_sun_size() -> f64 {
+    environment::DEFAULT_SUN_SIZE
+}
+
+fn default_coverage() -> f64 {
+    0.5
+}
+
+fn default_albedo() -> TextureDescription {
+    TextureDescription::Color([1.0, 1.0, 1.0])
+}
+
+fn vec3(v: [f64; 3]) -> Vec3<f64> {
+    Vec3 {
+        x: v[0],
+        y: v[1],
+        z: v[2],
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneFileError {
+    /// The file couldn't be read.
+    Io(io::Error),
+    /// The file isn't valid JSON or doesn't match the scene format.
+    Parse(serde_json::Error),
+    /// An object refers to a material that isn't in the materials table.
+    UnknownMaterial(String),
+    /// An OBJ model couldn't be loaded.
+    Obj(ObjError),
+    /// The environment map couldn't be loaded.
+    EnvironmentMap(ImageError),
+    /// An image texture couldn't be loaded.
+    Texture(PathBuf, ImageError),
+    /// A setting is out of range, like a sample count of zero.
+    Invalid(String),
+}
+
+impl fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneFileError::Io(err) => write!(f, "could not read scene file: {}", err),
+            SceneFileError::Parse(err) => write!(f, "invalid scene file: {}", err),
+            SceneFileError::UnknownMaterial(name) => {
+                write!(f, "scene file uses undefined material '{}'", name)
+            }
+            SceneFileError::Obj(err) => write!(f, "could not load model: {}", err),
+            SceneFileError::EnvironmentMap(err) => {
+                write!(f, "could not load environment map: {}", err)
+            }
+            SceneFileError::Texture(path, err) => {
+                write!(f, "could not load texture {}: {}", path.display(), err)
+            }
+            SceneFileError::Invalid(message) => write!(f, "invalid scene file: {}", message),
+        }
+    }
+}
+
+impl Error for SceneFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SceneFileError::Io(err) => Some(err),
+            SceneFileError::Parse(err) => Some(err),
+            SceneFileError::UnknownMaterial(_) => None,
+            SceneFileError::Obj(err) => Some(err),
+            SceneFileError::EnvironmentMap(err) => Some(err),
+            SceneFileError::Texture(_, err) => Some(err),
+            SceneFileError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for SceneFileError {
+    fn from(err: io::Error) -> Self {
+        SceneFileError::Io(err)
+    }
+}
+
+impl From<ObjError> for SceneFileError {
+    fn from(err: ObjError) -> Self {
+        SceneFileError::Obj(err)
+    }
+}
+
+impl From<serde_json::Error> for SceneFileError {
+    fn from(err: serde_json::Error) -> Self {
+        SceneFileError::Parse(err)
+    }
+}
+
+impl SceneFile {
+    /// Read a scene file from disk.  Paths in the file are made relative to the file's directory.
+    pub fn load(path: &Path) -> Result<SceneFile, SceneFileError> {
+        let mut file = SceneFile::from_json(&fs::read_to_string(path)?)?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        for object in file.objects.iter_mut() {
+            object.resolve_paths(dir);
+        }
+
+        if let EnvironmentDescription::Map { path, .. } = &mut file.environment {
+            *path = dir.join(&path);
+        }
+
+        for material in file.materials.values_mut() {
+            if let MaterialDescription::Lambertian { albedo }
+            | MaterialDescription::Metal { albedo, .. }
+            | MaterialDescription::Dielectric { albedo, .. }
+            | MaterialDescription::Isotropic { albedo } = material
+            {
+                albedo.resolve_paths(dir);
+            }
+        }
+
+        Ok(file)
+    }
+
+    pub fn from_json(json: &str) -> Result<SceneFile, SceneFileError> {
+        let file: SceneFile = serde_json::from_str(json)?;
+        file.render.validate()?;
+        file.camera.validate()?;
+        file.environment.validate()?;
+
+        Ok(file)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Build the scene's objects and camera settings.  The objects are wrapped in a BVH, and
+    /// objects made of `diffuse_light` are also gathered into the scene's lights.
+    pub fn scene(&self) -> Result<Scene, SceneFileError> {
+        let materials = self
+            .materials
+            .iter()
+            .map(|(name, desc)| Ok((name.as_str(), desc.build()?)))
+            .collect::<Result<BTreeMap<&str, Arc<dyn Material<f64>>>, SceneFileError>>()?;
+
+        let mut models = HashMap::new();
+        let mut objects = HittableList::new(Vec::new());
+        let mut lights = HittableList::new(Vec::new());
+
+        for desc in self.objects.iter() {
+            let object = desc.build(&materials, &mut models)?;
+
+            if desc.is_light(&self.materials) {
+                let light: Arc<dyn Hittable<f64>> = Arc::from(object);
+                objects.add(Box::new(light.clone()));
+                lights.add(Box::new(light));
+            } else {
+                objects.add(object);
+            }
+        }
+
+        let world = if objects.is_empty() {
+            objects
+        } else {
+            HittableList::new(vec![Box::new(FlatBvh::new(objects))])
+        };
+
+        Ok(Scene {
+            world,
+            lights,
+            camera: self.camera.settings(),
+            environment: self.environment.build()?,
+        })
+    }
+
+    /// Fill in the render settings from the file, using `base` for anything the file leaves out.
+    pub fn render_settings(&self, base: RenderSettings) -> RenderSettings {
+        RenderSettings {
+            width: self.render.width.unwrap_or(base.width),
+            height: self.render.height.unwrap_or(base.height),
+            samples_per_pixel: self
+                .render
+                .samples_per_pixel
+                .unwrap_or(base.samples_per_pixel),
+            max_depth: self.render.max_depth.unwrap_or(base.max_depth),
+            seed: self.render.seed.unwrap_or(base.seed),
+            threads: base.threads,
+            integrator: self
+                .render
+                .integrator
+                .as_ref()
+                .map_or(base.integrator, IntegratorDescription::kind),
+        }
+    }
+}
+
+impl RenderDescription {
+    /// Check the settings against the same limits as the CLI's options.
+    fn validate(&self) -> Result<(), SceneFileError> {
+        check(
+            !matches!(self.width, Some(n) if n < 2),
+            "width must be at least 2",
+        )?;
+        check(
+            !matches!(self.height, Some(n) if n < 2),
+            "height must be at least 2",
+        )?;
+        check(
+            !matches!(self.samples_per_pixel, Some(n) if n < 1),
+            "samples_per_pixel must be at least 1",
+        )?;
+        check(
+            !matches!(self.max_depth, Some(n) if n < 1),
+            "max_depth must be at least 1",
+        )?;
+
+        match self.integrator {
+            Some(IntegratorDescription::Depth { max_distance }) => check(
+                max_distance > 0.0,
+                "depth max_distance must be greater than zero",
+            ),
+            Some(IntegratorDescription::AmbientOcclusion { radius }) => check(
+                radius > 0.0,
+                "ambient_occlusion radius must be greater than zero",
+            ),
+            Some(IntegratorDescription::BounceHeatmap { max_rays }) => check(
+                max_rays > 0,
+                "bounce_heatmap max_rays must be greater than zero",
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl IntegratorDescription {
+    pub fn kind(&self) -> IntegratorKind {
+        match *self {
+            IntegratorDescription::Path => IntegratorKind::Path,
+            IntegratorDescription::Normals => IntegratorKind::Normals,
+            IntegratorDescription::Depth { max_distance } => IntegratorKind::Depth { max_distance },
+            IntegratorDescription::Albedo => IntegratorKind::Albedo,
+            IntegratorDescription::AmbientOcclusion { radius } => {
+                IntegratorKind::AmbientOcclusion { radius }
+            }
+            IntegratorDescription::BounceHeatmap { max_rays } => {
+                IntegratorKind::BounceHeatmap { max_rays }
+            }
+        }
+    }
+}
+
+impl CameraDescription {
+    /// Check the settings against the same limits as the CLI's options.
+    fn validate(&self) -> Result<(), SceneFileError> {
+        check(
+            self.vfov > 0.0 && self.vfov < 180.0,
+            "vfov must be between 0 and 180 degrees",
+        )?;
+        check(self.aperture >= 0.0, "aperture must not be negative")?;
+        check(
+            self.focus_dist > 0.0,
+            "focus_dist must be greater than zero",
+        )?;
+        check(
+            (0.0..=1.0).contains(&self.shutter_open) && (0.0..=1.0).contains(&self.shutter_close),
+            "shutter_open and shutter_close must be between 0 and 1",
+        )
+    }
+
+    pub fn settings(&self) -> CameraSettings<f64> {
+        CameraSettings {
+            lookfrom: vec3(self.lookfrom),
+            lookat: vec3(self.lookat),
+            vup: vec3(self.vup),
+            vfov: self.vfov,
+            aperture: self.aperture,
+            focus_dist: self.focus_dist,
+            shutter_open: self.shutter_open,
+            shutter_close: self.shutter_close,
+        }
+    }
+}
+
+impl EnvironmentDescription {
+    /// Check the settings against the same limits as the CLI's options.
+    fn validate(&self) -> Result<(), SceneFileError> {
+        match *self {
+            EnvironmentDescription::Map { intensity, .. } => check(
+                intensity >= 0.0,
+                "environment intensity must not be negative",
+            ),
+            EnvironmentDescription::Daylight {
+                turbidity,
+                intensity,
+                ..
+            } => {
+                check(turbidity > 0.0, "turbidity must be greater than zero")?;
+                check(
+                    intensity >= 0.0,
+                    "environment intensity must not be negative",
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn build(&self) -> Result<Environment<f64>, SceneFileError> {
+        let environment = match self {
+            EnvironmentDescription::Sky => Environment::sky(),
+            EnvironmentDescription::None => Environment::None,
+            EnvironmentDescription::Solid { color } => Environment::Solid(vec3(*color)),
+            EnvironmentDescription::Gradient { bottom, top } => Environment::Gradient {
+                bottom: vec3(*bottom),
+                top: vec3(*top),
+            },
+            EnvironmentDescription::Map {
+                path,
+                intensity,
+                rotation,
+            } => Environment::Map(Arc::new(
+                EnvironmentMap::load(path, *intensity, *rotation)
+                    .map_err(SceneFileError::EnvironmentMap)?,
+            )),
+            EnvironmentDescription::Daylight {
+                sun_elevation,
+                sun_azimuth,
+                turbidity,
+                sun_size,
+                intensity,
+            } => Environment::Daylight(Arc::new(DaylightSky::new(
+                *sun_elevation,
+                *sun_azimuth,
+                *turbidity,
+                *sun_size,
+                *intensity,
+            ))),
+        };
+
+        Ok(environment)
+    }
+}
+
+impl MaterialDescription {
+    fn build(&self) -> Result<Arc<dyn Material<f64>>, SceneFileError> {
+        let material: Arc<dyn Material<f64>> = match self {
+            MaterialDescription::Lambertian { albedo } => {
+                Arc::new(Lambertian::textured(albedo.build()?))
+            }
+            MaterialDescription::Metal { albedo, fuzz } => {
+                Arc::new(Metal::textured(albedo.build()?, *fuzz))
+            }
+            MaterialDescription::Dielectric { ir, albedo } => {
+                Arc::new(Dielectric::textured(*ir, albedo.build()?))
+            }
+            MaterialDescription::DiffuseLight { emit } => {
+                Arc::new(DiffuseLight { emit: vec3(*emit) })
+            }
+            MaterialDescription::Isotropic { albedo } => {
+                Arc::new(Isotropic::textured(albedo.build()?))
+            }
+        };
+
+        Ok(material)
+    }
+}
+
+impl TextureDescription {
+    fn build(&self) -> Result<Arc<dyn Texture<f64>>, SceneFileError> {
+        let texture: Arc<dyn Texture<f64>> = match self {
+            TextureDescription::Checker { scale, even, odd } => Arc::new(Checker {
+                scale: *scale,
+                even: even.build()?,
+                odd: odd.build()?,
+            }),
+            TextureDescription::Image { path, wrap, filter } => {
+                let mut texture = ImageTexture::load(path)
+                    .map_err(|err| SceneFileError::Texture(path.clone(), err))?;
+                texture.wrap = *wrap;
+                texture.filter = *filter;
+
+                Arc::new(texture)
+            }
+            TextureDescription::Marble {
+                scale,
+                base,
+                vein,
+                seed,
+            } => Arc::new(Marble::new(
+                *scale,
+                vec3(*base),
+                vec3(*vein),
+                &mut Rng::new(*seed),
+            )),
+            TextureDescription::Wood {
+                scale,
+                light,
+                dark,
+                seed,
+            } => Arc::new(Wood::new(
+                *scale,
+                vec3(*light),
+                vec3(*dark),
+                &mut Rng::new(*seed),
+            )),
+            TextureDescription::Clouds {
+                scale,
+                coverage,
+                sky,
+                cloud,
+                seed,
+            } => Arc::new(Clouds::new(
+                *scale,
+                *coverage,
+                vec3(*sky),
+                vec3(*cloud),
+                &mut Rng::new(*seed),
+            )),
+            TextureDescription::Color(color) => Arc::new(SolidColor {
+                color: vec3(*color),
+            }),
+        };
+
+        Ok(texture)
+    }
+
+    fn resolve_paths(&mut self, dir: &Path) {
+        match self {
+            TextureDescription::Checker { even, odd, .. } => {
+                even.resolve_paths(dir);
+                odd.resolve_paths(dir);
+            }
+            TextureDescription::Image { path, .. } => *path = dir.join(&path),
+            _ => {}
+        }
+    }
+}
+
+impl ObjectDescription {
+    /// Check whether the object is made of a material that gives off light.
+    fn is_light(&self, materials: &BTreeMap<String, MaterialDescription>) -> bool {
+        match self {
+            ObjectDescription::Sphere { material, .. }
+            | ObjectDescription::MovingSphere { material, .. }
+            | ObjectDescription::Triangle { material, .. }
+            | ObjectDescription::Quad { material, .. }
+            | ObjectDescription::Box { material, .. } => matches!(
+                materials.get(material),
+                Some(MaterialDescription::DiffuseLight { .. })
+            ),
+            ObjectDescription::Instance { object, .. } => object.is_light(materials),
+            ObjectDescription::Obj { .. } | ObjectDescription::ConstantMedium { .. } => false,
+        }
+    }
+
+    fn resolve_paths(&mut self, dir: &Path) {
+        match self {
+            ObjectDescription::Obj { path } => *path = dir.join(&path),
+            ObjectDescription::Instance { object, .. } => object.resolve_paths(dir),
+            ObjectDescription::ConstantMedium { boundary, .. } => boundary.resolve_paths(dir),
+            _ => {}
+        }
+    }
+
+    fn build(
+        &self,
+        materials: &BTreeMap<&str, Arc<dyn Material<f64>>>,
+        models: &mut HashMap<PathBuf, Arc<ObjModel<f64>>>,
+    ) -> Result<Box<dyn Hittable<f64>>, SceneFileError> {
+        let material = |name: &str| {
+            materials
+                .get(name)
+                .cloned()
+                .ok_or_else(|| SceneFileError::UnknownMaterial(name.to_string()))
+        };
+
+        match self {
+            ObjectDescription::Sphere {
+                center,
+                radius,
+                material: name,
+            } => Ok(Box::new(Sphere {
+                center: vec3(*center),
+                radius: *radius,
+                material: material(name)?,
+            })),
+            ObjectDescription::MovingSphere {
+                center0,
+                center1,
+                radius,
+                material: name,
+            } => Ok(Box::new(MovingSphere {
+                center0: vec3(*center0),
+                center1: vec3(*center1),
+                radius: *radius,
+                material: material(name)?,
+            })),
+            ObjectDescription::Triangle {
+                vertices,
+                material: name,
+            } => Ok(Box::new(Triangle::new(
+                vec3(vertices[0]),
+                vec3(vertices[1]),
+                vec3(vertices[2]),
+                material(name)?,
+            ))),
+            ObjectDescription::Quad {
+                q,
+                u,
+                v,
+                material: name,
+            } => Ok(Box::new(Quad::new(
+                vec3(*q),
+                vec3(*u),
+                vec3(*v),
+                material(name)?,
+            ))),
+            ObjectDescription::Box {
+                min,
+                max,
+                material: name,
+            } => Ok(Box::new(BoxShape::new(
+                vec3(*min),
+                vec3(*max),
+                material(name)?,
+            ))),
+            ObjectDescription::Obj { path } => {
+                let model = match models.get(path) {
+                    Some(model) => model.clone(),
+                    None => {
+                        let model = Arc::new(ObjModel::load(path)?);
+                        models.insert(path.clone(), model.clone());
+                        model
+                    }
+                };
+
+                Ok(Box::new(model))
+            }
+            ObjectDescription::ConstantMedium {
+                boundary,
+                density,
+                material: name,
+            } => {
+                check(
+                    *density > 0.0,
+                    "constant_medium density must be greater than zero",
+                )?;
+
+                Ok(Box::new(ConstantMedium::new(
+                    Arc::from(boundary.build(materials, models)?),
+                    *density,
+                    material(name)?,
+                )))
+            }
+            ObjectDescription::Instance {
+                object,
+                scale,
+                rotate,
+                translate,
+                end,
+            } => {
+                // Zero scale can't be undone, and an animated scale would pass through zero if it
+                // changed sign.
+                let end_scale = end.as_ref().and_then(|end| end.scale).unwrap_or(*scale);
+                check(
+                    (0..3).all(|axis| scale[axis] * end_scale[axis] > 0.0),
+                    "instance scale must not be zero or change sign",
+                )?;
+
+                let object = Arc::from(object.build(materials, models)?);
+                let start = Pose {
+                    scale: vec3(*scale),
+                    rotate: vec3(*rotate),
+                    translate: vec3(*translate),
+                };
+
+                Ok(Box::new(match end {
+                    Some(end) => {
+                        let end = Pose {
+                            scale: vec3(end.scale.unwrap_or(*scale)),
+                            rotate: vec3(end.rotate.unwrap_or(*rotate)),
+                            translate: vec3(end.translate.unwrap_or(*translate)),
+                        };
+
+                        Instance::animated(object, start, end)
+                    }
+                    None => Instance::new(object, start.transform()),
+                }))
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn scene_file_round_trip() {
+    let json = r#"{
+        "render": {
+            "width": 64,
+            "samples_per_pixel": 2,
+            "integrator": { "type": "ambient_occlusion", "radius": 0.25 }
+        },
+        "camera": { "lookfrom": [0, 1, 3], "lookat": [0, 0, 0], "vfov": 40 },
+        "environment": { "type": "solid", "color": [0.1, 0.2, 0.3] },
+        "materials": {
+            "red": { "type": "lambertian", "albedo": [0.8, 0.1, 0.1] },
+            "glass": { "type": "dielectric", "ir": 1.5 },
+            "floor": {
+                "type": "lambertian",
+                "albedo": { "type": "checker", "scale": 0.5, "even": [0, 0, 0], "odd": [1, 1, 1] }
+            }
+        },
+        "objects": [
+            { "type": "sphere", "center": [0, 0, 0], "radius": 0.5, "material": "red" },
+            { "type": "sphere", "center": [1, 0, 0], "radius": 0.5, "material": "glass" },
+            { "type": "triangle", "vertices": [[0, 0, 0], [1, 0, 0], [0, 1, 0]], "material": "red" },
+            {
+                "type": "moving_sphere",
+                "center0": [0, 1, 0], "center1": [0, 2, 0], "radius": 0.2, "material": "red"
+            },
+            {
+                "type": "instance",
+                "object": { "type": "box", "min": [0, 0, 0], "max": [1, 1, 1], "material": "red" },
+                "translate": [2, 0, 0],
+                "end": { "rotate": [0, 90, 0] }
+            }
+        ]
+    }"#;
+
+    let file = SceneFile::from_json(json).unwrap();
+
+    assert_eq!(file.camera.vup, [0.0, 1.0, 0.0]);
+    assert_eq!(
+        file.materials["glass"],
+        MaterialDescription::Dielectric {
+            ir: 1.5,
+            albedo: TextureDescription::Color([1.0, 1.0, 1.0])
+        }
+    );
+    assert!(matches!(
+        &file.materials["floor"],
+        MaterialDescription::Lambertian {
+            albedo: TextureDescription::Checker { scale, .. }
+        } if *scale == 0.5
+    ));
+
+    let settings = file.render_settings(RenderSettings::default());
+    assert_eq!(settings.width, 64);
+    assert_eq!(settings.samples_per_pixel, 2);
+    assert_eq!(settings.max_depth, RenderSettings::default().max_depth);
+    assert_eq!(
+        settings.integrator,
+        IntegratorKind::AmbientOcclusion { radius: 0.25 }
+    );
+
+    let scene = file.scene().unwrap();
+    assert_eq!(
+        scene.environment,
+        Environment::Solid(Vec3 {
+            x: 0.1,
+            y: 0.2,
+            z: 0.3
+        })
+    );
+
+    assert_eq!(SceneFile::from_json(&file.to_json()).unwrap(), file);
+}
+
+#[test]
+fn scene_file_errors() {
+    let unknown_material = r#"{
+        "camera": { "lookfrom": [0, 1, 3], "lookat": [0, 0, 0], "vfov": 40 },
+        "materials": {},
+        "objects": [
+            { "type": "sphere", "center": [0, 0, 0], "radius": 0.5, "material": "missing" }
+        ]
+    }"#;
+
+    match SceneFile::from_json(unknown_material).unwrap().scene() {
+        Err(SceneFileError::UnknownMaterial(name)) => assert_eq!(name, "missing"),
+        _ => panic!("expected an unknown material error"),
+    }
+
+    assert!(matches!(
+        SceneFile::from_json("{ \"camera\": {} }"),
+        Err(SceneFileError::Parse(_))
+    ));
+
+    // A model with nothing to render is an error rather than an object without a bounding box.
+    let empty_obj = std::env::temp_dir().join("rtw-scene-file-empty.obj");
+    fs::write(&empty_obj, "v 0 0 0\nv 1 0 0\n").unwrap();
+    let json = format!(
+        r#"{{
+            "camera": {{ "lookfrom": [0, 1, 3], "lookat": [0, 0, 0], "vfov": 40 }},
+            "materials": {{}},
+            "objects": [{{ "type": "obj", "path": {:?} }}]
+        }}"#,
+        empty_obj
+    );
+    let result = SceneFile::from_json(&json).unwrap().scene();
+    fs::remove_file(&empty_obj).unwrap();
+    assert!(matches!(
+        result,
+        Err(SceneFileError::Obj(ObjError::NoFaces { .. }))
+    ));
+
+    let flattened = r#"{
+        "camera": { "lookfrom": [0, 1, 3], "lookat": [0, 0, 0], "vfov": 40 },
+        "materials": { "white": { "type": "lambertian", "albedo": [1, 1, 1] } },
+        "objects": [
+            {
+                "type": "instance",
+                "object": { "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "white" },
+                "scale": [1, 0, 1]
+            }
+        ]
+    }"#;
+    assert!(matches!(
+        SceneFile::from_json(flattened).unwrap().scene(),
+        Err(SceneFileError::Invalid(_))
+    ));
+
+    let no_fog = r#"{
+        "camera": { "lookfrom": [0, 1, 3], "lookat": [0, 0, 0], "vfov": 40 },
+        "materials": { "fog": { "type": "isotropic", "albedo": [1, 1, 1] } },
+        "objects": [
+            {
+                "type": "constant_medium",
+                "boundary": { "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "fog" },
+                "density": 0,
+                "material": "fog"
+            }
+        ]
+    }"#;
+    assert!(matches!(
+        SceneFile::from_json(no_fog).unwrap().scene(),
+        Err(SceneFileError::Invalid(_))
+    ));
+
+    // Render settings have the same limits as the CLI's options.
+    let with_render = |render: &str| {
+        SceneFile::from_json(&format!(
+            r#"{{
+                "render": {},
+                "camera": {{ "lookfrom": [0, 1, 3], "lookat": [0, 0, 0], "vfov": 40 }},
+                "materials": {{}},
+                "objects": []
+            }}"#,
+            render
+        ))
+    };
+
+    assert!(with_render(r#"{ "width": 2, "height": 2, "samples_per_pixel": 1 }"#).is_ok());
+
+    // So do the camera and environment.
+    let with_camera = |camera: &str, environment: &str| {
+        SceneFile::from_json(&format!(
+            r#"{{
+                "camera": {{ "lookfrom": [0, 1, 3], "lookat": [0, 0, 0], {} }},
+                "environment": {},
+                "materials": {{}},
+                "objects": []
+            }}"#,
+            camera, environment
+        ))
+    };
+
+    assert!(with_camera(r#""vfov": 40"#, r#"{ "type": "sky" }"#).is_ok());
+
+    for (camera, environment) in [
+        (r#""vfov": 40, "shutter_close": 2"#, r#"{ "type": "sky" }"#),
+        (r#""vfov": 0"#, r#"{ "type": "sky" }"#),
+        (r#""vfov": 180"#, r#"{ "type": "sky" }"#),
+        (r#""vfov": 40, "aperture": -0.1"#, r#"{ "type": "sky" }"#),
+        (r#""vfov": 40, "focus_dist": -1"#, r#"{ "type": "sky" }"#),
+        (
+            r#""vfov": 40"#,
+            r#"{ "type": "daylight", "sun_elevation": 30, "turbidity": 0 }"#,
+        ),
+        (
+            r#""vfov": 40"#,
+            r#"{ "type": "daylight", "sun_elevation": 30, "intensity": -1 }"#,
+        ),
+        (
+            r#""vfov": 40"#,
+            r#"{ "type": "map", "path": "sky.hdr", "intensity": -1 }"#,
+        ),
+    ] {
+        assert!(
+            matches!(
+                with_camera(camera, environment),
+                Err(SceneFileError::Invalid(_))
+            ),
+            "{} {}",
+            camera,
+            environment
+        );
+    }
+
+    for render in [
+        r#"{ "width": 1 }"#,
+        r#"{ "height": 1 }"#,
+        r#"{ "samples_per_pixel": 0 }"#,
+        r#"{ "max_depth": 0 }"#,
+        r#"{ "integrator": { "type": "depth", "max_distance": 0 } }"#,
+        r#"{ "integrator": { "type": "ambient_occlusion", "radius": -1 } }"#,
+        r#"{ "integrator": { "type": "bounce_heatmap", "max_rays": 0 } }"#,
+    ] {
+        assert!(
+            matches!(with_render(render), Err(SceneFileError::Invalid(_))),
+            "{}",
+            render
+        );
+    }
+}
+
+/// The example files in /scenes are ports of the built-in scenes and should render identically.
+#[test]
+fn example_scene_files_match_built_in_scenes() {
+    use crate::render::Renderer;
+    use crate::scenes;
+
+    for name in scenes::NAMES.iter() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../scenes")
+            .join(format!("{}.json", name));
+
+        let file = SceneFile::load(&path).unwrap();
+        let from_file = file.scene().unwrap();
+        let built_in = scenes::by_name(name).unwrap();
+
+        assert_eq!(from_file.camera, built_in.camera);
+        assert_eq!(from_file.environment, built_in.environment);
+
+        let settings = RenderSettings {
+            width: 24,
+            height: 16,
+            samples_per_pixel: 2,
+            ..Default::default()
+        };
+        let camera = built_in.camera.build(settings.aspect_ratio());
+
+        assert_eq!(from_file.lights.len(), built_in.lights.len());
+
+        let render = |scene: &Scene| {
+            Renderer::new(
+                &scene.world,
+                &scene.lights,
+                &scene.environment,
+                &camera,
+                settings,
+            )
+            .render()
+        };
+        let (a, b) = (render(&from_file), render(&built_in));
+
+        assert_eq!(a.pixels, b.pixels, "{} differs from its scene file", name);
+    }
+}
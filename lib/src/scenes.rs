@@ -9,3 +9,86 @@ pub mod glass_sphere_scene;
 
 /// A simple scene with three spheres.
 pub mod three_sphere_scene;
+
+/// The Cornell box, lit by a ceiling light.
+pub mod cornell_box;
+
+use crate::camera::CameraSettings;
+use crate::environment::Environment;
+use crate::hit::HittableList;
+
+/// The names of the built-in scenes, as accepted by `by_name`.
+pub const NAMES: [&str; 5] = [
+    "random_scene",
+    "ten_spheres",
+    "glass_sphere_scene",
+    "three_sphere_scene",
+    "cornell_box",
+];
+
+/// A built-in scene along with the camera settings that frame it and what lies beyond it.
+pub struct Scene {
+    pub world: HittableList<f64>,
+    /// The objects in `world` that give off light, which are sampled directly.  Empty for scenes
+    /// lit only by their environment.
+    pub lights: HittableList<f64>,
+    pub camera: CameraSettings<f64>,
+    pub environment: Environment<f64>,
+}
+
+/// Create one of the built-in scenes by its module name, or None if there is no such scene.
+pub fn by_name(name: &str) -> Option<Scene> {
+    let sky = Environment::sky();
+
+    let no_lights = || HittableList::new(Vec::new());
+
+    let (world, lights, camera, environment) = match name {
+        "random_scene" => (
+            random_scene::scene(),
+            no_lights(),
+            random_scene::camera(),
+            sky,
+        ),
+        "ten_spheres" => (
+            ten_spheres::scene(),
+            no_lights(),
+            ten_spheres::camera(),
+            sky,
+        ),
+        "glass_sphere_scene" => (
+            glass_sphere_scene::scene(),
+            no_lights(),
+            glass_sphere_scene::camera(),
+            sky,
+        ),
+        "three_sphere_scene" => (
+            three_sphere_scene::scene(),
+            no_lights(),
+            three_sphere_scene::camera(),
+            sky,
+        ),
+        "cornell_box" => (
+            cornell_box::scene(),
+            cornell_box::lights(),
+            cornell_box::camera(),
+            Environment::None,
+        ),
+        _ => return None,
+    };
+
+    Some(Scene {
+        world,
+        lights,
+        camera,
+        environment,
+    })
+}
+
+#[test]
+fn every_scene_name_loads() {
+    for name in NAMES.iter() {
+        assert!(by_name(name).is_some(), "{} should load", name);
+    }
+
+    assert!(by_name("no_such_scene").is_none());
+}
@@ -0,0 +1,155 @@
+use crate::camera::CameraSettings;
+use crate::hit::HittableList;
+use crate::material;
+use crate::objects::box_shape::BoxShape;
+use crate::objects::instance::Instance;
+use crate::objects::quad::Quad;
+use crate::transform::Transform;
+use crate::vec::Color;
+use crate::vec::{Point3, Vec3};
+use std::sync::Arc;
+
+/// Create the Cornell box: a white room with a green wall on the left, a red wall on the right, a
+/// light in the ceiling and two white boxes.  It is lit only by the ceiling light.
+pub fn scene() -> HittableList<f64> {
+    let mut world = HittableList::new(Vec::new());
+
+    // Materials
+
+    let red = Arc::new(material::Lambertian::new(Color {
+        x: 0.65,
+        y: 0.05,
+        z: 0.05,
+    }));
+    let white = Arc::new(material::Lambertian::new(Color {
+        x: 0.73,
+        y: 0.73,
+        z: 0.73,
+    }));
+    let green = Arc::new(material::Lambertian::new(Color {
+        x: 0.12,
+        y: 0.45,
+        z: 0.15,
+    }));
+    let point = |x, y, z| Point3 { x, y, z };
+    let vec = |x, y, z| Vec3 { x, y, z };
+
+    // Walls, facing into the room
+
+    world.add(Box::new(Quad::new(
+        point(555.0, 0.0, 0.0),
+        vec(0.0, 0.0, 555.0),
+        vec(0.0, 555.0, 0.0),
+        green,
+    )));
+    world.add(Box::new(Quad::new(
+        point(0.0, 0.0, 0.0),
+        vec(0.0, 555.0, 0.0),
+        vec(0.0, 0.0, 555.0),
+        red,
+    )));
+    world.add(Box::new(Quad::new(
+        point(0.0, 0.0, 0.0),
+        vec(0.0, 0.0, 555.0),
+        vec(555.0, 0.0, 0.0),
+        white.clone(),
+    )));
+    world.add(Box::new(Quad::new(
+        point(555.0, 555.0, 555.0),
+        vec(-555.0, 0.0, 0.0),
+        vec(0.0, 0.0, -555.0),
+        white.clone(),
+    )));
+    world.add(Box::new(Quad::new(
+        point(0.0, 0.0, 555.0),
+        vec(0.0, 555.0, 0.0),
+        vec(555.0, 0.0, 0.0),
+        white.clone(),
+    )));
+
+    world.add(Box::new(light()));
+
+    // Boxes, turned to face the camera a little
+
+    let tall = Arc::new(BoxShape::new(
+        Point3::zero(),
+        point(165.0, 330.0, 165.0),
+        white.clone(),
+    ));
+    world.add(Box::new(Instance::new(
+        tall,
+        Transform::rotate_y(15.0).then(&Transform::translate(vec(265.0, 0.0, 295.0))),
+    )));
+
+    let short = Arc::new(BoxShape::new(
+        Point3::zero(),
+        point(165.0, 165.0, 165.0),
+        white,
+    ));
+    world.add(Box::new(Instance::new(
+        short,
+        Transform::rotate_y(-18.0).then(&Transform::translate(vec(130.0, 0.0, 65.0))),
+    )));
+
+    world
+}
+
+/// Get the objects in the scene that give off light, for sampling them directly.
+pub fn lights() -> HittableList<f64> {
+    HittableList::new(vec![Box::new(light())])
+}
+
+/// The ceiling light, just below the ceiling.
+fn light() -> Quad<f64> {
+    let light = Arc::new(material::DiffuseLight {
+        emit: Color {
+            x: 15.0,
+            y: 15.0,
+            z: 15.0,
+        },
+    });
+
+    Quad::new(
+        Point3 {
+            x: 343.0,
+            y: 554.0,
+            z: 332.0,
+        },
+        Vec3 {
+            x: -130.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -105.0,
+        },
+        light,
+    )
+}
+
+pub fn camera() -> CameraSettings<f64> {
+    CameraSettings {
+        lookfrom: Point3 {
+            x: 278.0,
+            y: 278.0,
+            z: -800.0,
+        },
+        lookat: Point3 {
+            x: 278.0,
+            y: 278.0,
+            z: 0.0,
+        },
+        vup: Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        vfov: 40.0,
+        aperture: 0.0,
+        focus_dist: 10.0,
+        shutter_open: 0.0,
+        shutter_close: 0.0,
+    }
+}
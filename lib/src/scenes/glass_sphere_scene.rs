@@ -1,9 +1,10 @@
+use crate::camera::CameraSettings;
 use crate::hit::HittableList;
 use crate::material;
 use crate::objects::sphere::Sphere;
 use crate::vec::Color;
-use crate::vec::Point3;
-use std::rc::Rc;
+use crate::vec::{Point3, Vec3};
+use std::sync::Arc;
 
 /// Create a scene with five large spheres in the background and five glassy colored spheres in the
 /// foreground.
@@ -13,56 +14,50 @@ pub fn scene() -> HittableList<f64> {
 
     // Materials
 
-    let default_material = Rc::new(material::Lambertian {
-        albedo: Color {
-            x: 122.0 / 255.0,
-            y: 175.0 / 255.0,
-            z: 238.0 / 255.0,
-        },
-    });
+    let default_material = Arc::new(material::Lambertian::new(Color {
+        x: 122.0 / 255.0,
+        y: 175.0 / 255.0,
+        z: 238.0 / 255.0,
+    }));
 
-    let grey_material = Rc::new(material::Lambertian {
-        albedo: Color {
-            x: 1.0,
-            y: 1.0,
-            z: 1.0,
-        },
-    });
+    let grey_material = Arc::new(material::Lambertian::new(Color {
+        x: 1.0,
+        y: 1.0,
+        z: 1.0,
+    }));
 
-    let ground_material = Rc::new(material::Lambertian {
-        albedo: Color {
-            x: 72.0 / 255.0,
-            y: 72.0 / 255.0,
-            z: 72.0 / 255.0,
-        },
-    });
+    let ground_material = Arc::new(material::Lambertian::new(Color {
+        x: 72.0 / 255.0,
+        y: 72.0 / 255.0,
+        z: 72.0 / 255.0,
+    }));
 
-    let metal_material = Rc::new(material::Metal {
-        albedo: Color {
+    let metal_material = Arc::new(material::Metal::new(
+        Color {
             x: 64.0 / 255.0,
             y: 64.0 / 255.0,
             z: 64.0 / 255.0,
         },
-        fuzz: 0.1,
-    });
+        0.1,
+    ));
 
-    let mirror_material = Rc::new(material::Metal {
-        albedo: Color {
+    let mirror_material = Arc::new(material::Metal::new(
+        Color {
             x: 253.0 / 255.0,
             y: 253.0 / 255.0,
             z: 1.0,
         },
-        fuzz: 0.0,
-    });
+        0.0,
+    ));
 
-    let metal_red_material = Rc::new(material::Metal {
-        albedo: Color {
+    let metal_red_material = Arc::new(material::Metal::new(
+        Color {
             x: 208.0 / 255.0,
             y: 66.0 / 255.0,
             z: 70.0 / 255.0,
         },
-        fuzz: 0.3,
-    });
+        0.3,
+    ));
 
     // Sphere 1
     world.add(Box::new(Sphere {
@@ -126,14 +121,14 @@ pub fn scene() -> HittableList<f64> {
             z: -1.0,
         },
         radius: 0.9,
-        material: Rc::new(material::Dielectric {
-            ir: 1.5,
-            albedo: Color {
+        material: Arc::new(material::Dielectric::new(
+            1.5,
+            Color {
                 x: 1.0,
                 y: 1.0,
                 z: 1.0,
             },
-        }),
+        )),
     }));
 
     // "World" sphere
@@ -149,3 +144,29 @@ pub fn scene() -> HittableList<f64> {
 
     world
 }
+
+/// Get camera settings looking down at the spheres.
+pub fn camera() -> CameraSettings<f64> {
+    CameraSettings {
+        lookfrom: Point3 {
+            x: 0.0,
+            y: 1.8,
+            z: 1.4,
+        },
+        lookat: Point3 {
+            x: 0.0,
+            y: 0.0,
+            z: -3.0,
+        },
+        vup: Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        vfov: 45.0,
+        aperture: 0.0,
+        focus_dist: 10.0,
+        shutter_open: 0.0,
+        shutter_close: 0.0,
+    }
+}
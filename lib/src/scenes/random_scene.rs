@@ -1,24 +1,33 @@
+use crate::bvh::FlatBvh;
+use crate::camera::CameraSettings;
 use crate::hit::HittableList;
 use crate::material;
 use crate::objects::sphere::Sphere;
-use crate::random::{random_float, random_float_in_range};
+use crate::random::{Rng, DEFAULT_SEED};
 use crate::vec::Color;
-use crate::vec::Point3;
-use std::rc::Rc;
+use crate::vec::{Point3, Vec3};
+use std::sync::Arc;
 
+/// Create the scene from the cover of Ray Tracing in One Weekend, wrapped in a BVH.
 #[allow(dead_code)]
 pub fn scene() -> HittableList<f64> {
+    HittableList::new(vec![Box::new(FlatBvh::new(objects()))])
+}
+
+/// Create the scene's spheres as a plain list, without a BVH.
+pub fn objects() -> HittableList<f64> {
     let mut world = HittableList::new(Vec::new());
 
+    // Always seed the same way so the spheres land in the same places every time.
+    let mut rng = Rng::new(DEFAULT_SEED);
+
     // Ground
 
-    let ground_material = Rc::new(material::Lambertian {
-        albedo: Color {
-            x: 80.0 / 255.0,
-            y: 144.0 / 255.0,
-            z: 22.0 / 255.0,
-        },
-    });
+    let ground_material = Arc::new(material::Lambertian::new(Color {
+        x: 80.0 / 255.0,
+        y: 144.0 / 255.0,
+        z: 22.0 / 255.0,
+    }));
 
     world.add(Box::new(Sphere {
         center: Point3 {
@@ -32,14 +41,14 @@ pub fn scene() -> HittableList<f64> {
 
     // Sphere 1
 
-    let material1 = Rc::new(material::Dielectric {
-        albedo: Color {
+    let material1 = Arc::new(material::Dielectric::new(
+        1.5,
+        Color {
             x: 242.0 / 255.0,
             y: 111.0 / 255.0,
             z: 112.0 / 255.0,
         },
-        ir: 1.5,
-    });
+    ));
 
     world.add(Box::new(Sphere {
         center: Point3 {
@@ -53,13 +62,11 @@ pub fn scene() -> HittableList<f64> {
 
     // Sphere 2
 
-    let material2 = Rc::new(material::Lambertian {
-        albedo: Color {
-            x: 111.0 / 255.0,
-            y: 165.0 / 255.0,
-            z: 242.0 / 255.0,
-        },
-    });
+    let material2 = Arc::new(material::Lambertian::new(Color {
+        x: 111.0 / 255.0,
+        y: 165.0 / 255.0,
+        z: 242.0 / 255.0,
+    }));
 
     world.add(Box::new(Sphere {
         center: Point3 {
@@ -73,14 +80,14 @@ pub fn scene() -> HittableList<f64> {
 
     // Sphere 3
 
-    let material3 = Rc::new(material::Metal {
-        albedo: Color {
+    let material3 = Arc::new(material::Metal::new(
+        Color {
             x: 0.7,
             y: 0.6,
             z: 0.5,
         },
-        fuzz: 0.0,
-    });
+        0.0,
+    ));
 
     world.add(Box::new(Sphere {
         center: Point3 {
@@ -100,18 +107,18 @@ pub fn scene() -> HittableList<f64> {
 
     for a in -11..11 {
         for b in -11..11 {
-            let choose_mat: f64 = random_float();
+            let choose_mat: f64 = rng.random_float();
 
             let center = Point3 {
-                x: (a as f64) + 0.9 * random_float::<f64>(),
+                x: (a as f64) + 0.9 * rng.random_float::<f64>(),
                 y: 0.2,
-                z: (b as f64) + 0.9 * random_float::<f64>(),
+                z: (b as f64) + 0.9 * rng.random_float::<f64>(),
             };
 
             if (center - boundary).length() > 0.9 {
                 if choose_mat < 0.66 {
-                    let albedo = Color::random() * Color::random();
-                    let sphere_material = Rc::new(material::Lambertian { albedo });
+                    let albedo = Color::random(&mut rng) * Color::random(&mut rng);
+                    let sphere_material = Arc::new(material::Lambertian::new(albedo));
 
                     world.add(Box::new(Sphere {
                         center,
@@ -119,9 +126,9 @@ pub fn scene() -> HittableList<f64> {
                         material: sphere_material,
                     }));
                 } else if choose_mat < 0.85 {
-                    let albedo = Color::random_range(0.5, 1.0);
-                    let fuzz = random_float_in_range(0.0, 0.5);
-                    let sphere_material = Rc::new(material::Metal { albedo, fuzz });
+                    let albedo = Color::random_range(&mut rng, 0.5, 1.0);
+                    let fuzz = rng.random_float_in_range(0.0, 0.5);
+                    let sphere_material = Arc::new(material::Metal::new(albedo, fuzz));
 
                     world.add(Box::new(Sphere {
                         center,
@@ -129,8 +136,8 @@ pub fn scene() -> HittableList<f64> {
                         material: sphere_material,
                     }));
                 } else {
-                    let albedo = Color::random_range(0.8, 1.0);
-                    let sphere_material = Rc::new(material::Dielectric { albedo, ir: 1.5 });
+                    let albedo = Color::random_range(&mut rng, 0.8, 1.0);
+                    let sphere_material = Arc::new(material::Dielectric::new(1.5, albedo));
 
                     world.add(Box::new(Sphere {
                         center,
@@ -144,3 +151,29 @@ pub fn scene() -> HittableList<f64> {
 
     world
 }
+
+/// Get the camera settings from the book's cover image.
+pub fn camera() -> CameraSettings<f64> {
+    CameraSettings {
+        lookfrom: Point3 {
+            x: 13.0,
+            y: 2.0,
+            z: 3.0,
+        },
+        lookat: Point3 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        vup: Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        vfov: 20.0,
+        aperture: 0.1,
+        focus_dist: 10.0,
+        shutter_open: 0.0,
+        shutter_close: 0.0,
+    }
+}
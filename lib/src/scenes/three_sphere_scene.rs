@@ -1,9 +1,10 @@
+use crate::camera::CameraSettings;
 use crate::hit::HittableList;
 use crate::material;
 use crate::objects::sphere::Sphere;
 use crate::vec::Color;
-use crate::vec::Point3;
-use std::rc::Rc;
+use crate::vec::{Point3, Vec3};
+use std::sync::Arc;
 
 /// Create a simple, efficient scene with three spheres.
 #[allow(dead_code)]
@@ -12,30 +13,26 @@ pub fn scene() -> HittableList<f64> {
 
     // Materials
 
-    let default_material = Rc::new(material::Lambertian {
-        albedo: Color {
-            x: 122.0 / 255.0,
-            y: 175.0 / 255.0,
-            z: 238.0 / 255.0,
-        },
-    });
+    let default_material = Arc::new(material::Lambertian::new(Color {
+        x: 122.0 / 255.0,
+        y: 175.0 / 255.0,
+        z: 238.0 / 255.0,
+    }));
 
-    let ground_material = Rc::new(material::Lambertian {
-        albedo: Color {
-            x: 28.0 / 255.0,
-            y: 28.0 / 255.0,
-            z: 28.0 / 255.0,
-        },
-    });
+    let ground_material = Arc::new(material::Lambertian::new(Color {
+        x: 28.0 / 255.0,
+        y: 28.0 / 255.0,
+        z: 28.0 / 255.0,
+    }));
 
-    let mirror_material = Rc::new(material::Metal {
-        albedo: Color {
+    let mirror_material = Arc::new(material::Metal::new(
+        Color {
             x: 224.0 / 255.0,
             y: 232.0 / 255.0,
             z: 245.0 / 255.0,
         },
-        fuzz: 0.0,
-    });
+        0.0,
+    ));
 
     // Mirror sphere
     world.add(Box::new(Sphere {
@@ -67,14 +64,14 @@ pub fn scene() -> HittableList<f64> {
             z: -0.1,
         },
         radius: 0.22,
-        material: Rc::new(material::Dielectric {
-            ir: 1.5,
-            albedo: Color {
+        material: Arc::new(material::Dielectric::new(
+            1.5,
+            Color {
                 x: 1.0,
                 y: 1.0,
                 z: 1.0,
             },
-        }),
+        )),
     }));
 
     // "World" sphere
@@ -90,3 +87,29 @@ pub fn scene() -> HittableList<f64> {
 
     world
 }
+
+/// Get camera settings looking down at the three spheres.
+pub fn camera() -> CameraSettings<f64> {
+    CameraSettings {
+        lookfrom: Point3 {
+            x: 0.0,
+            y: 1.8,
+            z: 1.4,
+        },
+        lookat: Point3 {
+            x: 0.0,
+            y: 0.0,
+            z: -3.0,
+        },
+        vup: Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        vfov: 45.0,
+        aperture: 0.0,
+        focus_dist: 10.0,
+        shutter_open: 0.0,
+        shutter_close: 0.0,
+    }
+}
@@ -0,0 +1,23 @@
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                            TEXTURES                                            //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+mod checker;
+mod image;
+pub mod perlin;
+mod procedural;
+mod solid;
+
+pub use self::image::{Filter, ImageTexture, Wrap};
+pub use checker::Checker;
+pub use procedural::{Clouds, Marble, Wood};
+pub use solid::SolidColor;
+
+use crate::vec::{Color, Point3};
+use num::Float;
+
+/// A color that varies over a surface, looked up by the hit point's surface coordinates (u, v) or
+/// by its position in space.
+pub trait Texture<T: Float>: Send + Sync {
+    fn value(&self, u: T, v: T, p: &Point3<T>) -> Color<T>;
+}
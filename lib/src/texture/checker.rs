@@ -0,0 +1,59 @@
+///////////////
+//  CHECKER  //
+///////////////
+
+use crate::texture::{SolidColor, Texture};
+use crate::vec::{Color, Point3};
+use num::Float;
+use std::sync::Arc;
+
+/// A 3D checkerboard of cubes alternating between two textures.  Being solid, it doesn't need
+/// surface coordinates and has no seams.
+pub struct Checker<T: Float> {
+    /// Side length of each cube.
+    pub scale: T,
+    pub even: Arc<dyn Texture<T>>,
+    pub odd: Arc<dyn Texture<T>>,
+}
+
+impl<T: Float + Send + Sync + 'static> Checker<T> {
+    /// Create a checkerboard of two colors.
+    pub fn colors(scale: T, even: Color<T>, odd: Color<T>) -> Checker<T> {
+        Checker {
+            scale,
+            even: Arc::new(SolidColor { color: even }),
+            odd: Arc::new(SolidColor { color: odd }),
+        }
+    }
+}
+
+impl<T: Float + Send + Sync> Texture<T> for Checker<T> {
+    fn value(&self, u: T, v: T, p: &Point3<T>) -> Color<T> {
+        let cell = |x: T| (x / self.scale).floor().to_i64().unwrap_or(0);
+        let sum = cell(p.x) + cell(p.y) + cell(p.z);
+
+        if sum.rem_euclid(2) == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn checker_alternates() {
+    let black = Color::zero();
+    let white = Color::one();
+    let checker = Checker::colors(0.5, black, white);
+
+    let at = |x, y, z| checker.value(0.0, 0.0, &Point3 { x, y, z });
+
+    assert_eq!(at(0.1, 0.1, 0.1), black);
+    assert_eq!(at(0.6, 0.1, 0.1), white);
+    assert_eq!(at(0.6, 0.6, 0.1), black);
+    assert_eq!(at(-0.1, 0.1, 0.1), white);
+}
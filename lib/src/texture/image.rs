@@ -0,0 +1,190 @@
+/////////////////////
+//  IMAGE TEXTURE  //
+/////////////////////
+
+use crate::texture::Texture;
+use crate::vec::{Color, Point3};
+use ::image::{DynamicImage, GenericImageView, ImageResult};
+use num::Float;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// What an image texture does with surface coordinates outside [0, 1].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Wrap {
+    /// Tile the image.
+    #[default]
+    Repeat,
+    /// Stretch the edge pixels outward.
+    Clamp,
+}
+
+/// How an image texture blends pixels when a lookup falls between them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    /// Use the closest pixel, for a blocky look.
+    Nearest,
+    /// Blend the four closest pixels.
+    #[default]
+    Bilinear,
+}
+
+/// An image wrapped over a surface by its (u, v) coordinates.  u runs left to right and v bottom to
+/// top, so (0, 0) is the bottom left corner of the image.
+pub struct ImageTexture<T: Float> {
+    width: usize,
+    height: usize,
+    /// Row by row, top to bottom, in linear color.
+    pixels: Vec<Color<T>>,
+    pub wrap: Wrap,
+    pub filter: Filter,
+}
+
+impl<T: Float> ImageTexture<T> {
+    /// Create a texture from linear colors stored row by row, top to bottom.  It repeats and is
+    /// filtered bilinearly.
+    ///
+    /// Panics if there isn't exactly one pixel for each position in the image.
+    pub fn new(width: usize, height: usize, pixels: Vec<Color<T>>) -> ImageTexture<T> {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "An image texture needs width * height pixels."
+        );
+        assert!(width > 0 && height > 0, "An image texture can't be empty.");
+
+        ImageTexture {
+            width,
+            height,
+            pixels,
+            wrap: Wrap::default(),
+            filter: Filter::default(),
+        }
+    }
+
+    /// Load a PNG, JPEG or other image supported by the `image` crate.  The image is taken to be in
+    /// sRGB, as color images almost always are, and converted to linear color.
+    pub fn load(path: &Path) -> ImageResult<ImageTexture<T>> {
+        let image = ::image::open(path)?;
+        let (width, height) = image.dimensions();
+
+        // Keep the extra precision of 16-bit images, but don't widen 8-bit ones, which the image
+        // crate does by shifting rather than scaling.
+        let channels: Vec<f64> = match image {
+            DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgb16(_)
+            | DynamicImage::ImageRgba16(_) => image
+                .into_rgb16()
+                .into_raw()
+                .into_iter()
+                .map(|c| c as f64 / u16::MAX as f64)
+                .collect(),
+            _ => image
+                .into_rgb8()
+                .into_raw()
+                .into_iter()
+                .map(|c| c as f64 / u8::MAX as f64)
+                .collect(),
+        };
+
+        let pixels = channels
+            .chunks(3)
+            .map(|p| Color {
+                x: srgb_to_linear(p[0]),
+                y: srgb_to_linear(p[1]),
+                z: srgb_to_linear(p[2]),
+            })
+            .collect();
+
+        Ok(ImageTexture::new(width as usize, height as usize, pixels))
+    }
+
+    /// Get a pixel, applying the wrap mode to coordinates outside the image.
+    fn pixel(&self, x: i64, y: i64) -> Color<T> {
+        let (w, h) = (self.width as i64, self.height as i64);
+
+        let (x, y) = match self.wrap {
+            Wrap::Repeat => (x.rem_euclid(w), y.rem_euclid(h)),
+            Wrap::Clamp => (x.clamp(0, w - 1), y.clamp(0, h - 1)),
+        };
+
+        self.pixels[y as usize * self.width + x as usize]
+    }
+}
+
+impl<T: Float + Send + Sync> Texture<T> for ImageTexture<T> {
+    fn value(&self, u: T, v: T, _p: &Point3<T>) -> Color<T> {
+        // Position in pixels, measured from the top left corner of the image.
+        let x = u.to_f64().unwrap_or(0.0) * self.width as f64;
+        let y = (1.0 - v.to_f64().unwrap_or(0.0)) * self.height as f64;
+
+        match self.filter {
+            Filter::Nearest => self.pixel(x.floor() as i64, y.floor() as i64),
+            Filter::Bilinear => {
+                // Pixel centers are at half-integer positions.
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let fx = T::from(x - x0).unwrap();
+                let fy = T::from(y - y0).unwrap();
+                let (x0, y0) = (x0 as i64, y0 as i64);
+
+                let top = self.pixel(x0, y0) * (T::one() - fx) + self.pixel(x0 + 1, y0) * fx;
+                let bottom =
+                    self.pixel(x0, y0 + 1) * (T::one() - fx) + self.pixel(x0 + 1, y0 + 1) * fx;
+
+                top * (T::one() - fy) + bottom * fy
+            }
+        }
+    }
+}
+
+/// Convert an sRGB-encoded channel in [0, 1] to linear light.
+fn srgb_to_linear<T: Float>(c: f64) -> T {
+    let linear = if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    };
+
+    T::from(linear).unwrap()
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn image_texture_lookups() {
+    use ::image::{Rgb, RgbImage};
+
+    // Black on the left and white on the right.
+    let mut image = RgbImage::new(2, 1);
+    image.put_pixel(1, 0, Rgb([255, 255, 255]));
+
+    let path = std::env::temp_dir().join(format!("rtw-test-{}.png", std::process::id()));
+    image.save(&path).unwrap();
+    let mut texture: ImageTexture<f64> = ImageTexture::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let at = |texture: &ImageTexture<f64>, u| texture.value(u, 0.5, &Point3::zero()).x;
+
+    // Halfway between the pixel centers is an even blend, and past the right edge it wraps back
+    // toward black.
+    assert_eq!(at(&texture, 0.25), 0.0);
+    assert_eq!(at(&texture, 0.5), 0.5);
+    assert_eq!(at(&texture, 0.75), 1.0);
+    assert_eq!(at(&texture, 1.0), 0.5);
+
+    texture.wrap = Wrap::Clamp;
+    assert_eq!(at(&texture, 1.0), 1.0);
+
+    texture.filter = Filter::Nearest;
+    assert_eq!(at(&texture, 0.49), 0.0);
+    assert_eq!(at(&texture, 0.51), 1.0);
+
+    // sRGB mid-grey is much darker in linear light.
+    assert!((srgb_to_linear::<f64>(0.5) - 0.214).abs() < 0.001);
+}
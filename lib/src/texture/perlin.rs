@@ -0,0 +1,145 @@
+////////////////////
+//  PERLIN NOISE  //
+////////////////////
+
+use crate::random::Rng;
+use crate::vec::{Point3, Vec3};
+
+const POINT_COUNT: usize = 256;
+
+/// Smoothly varying gradient noise.  The lattice of random gradients comes from an `Rng`, so the
+/// same seed always gives the same noise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Perlin {
+    gradients: Vec<Vec3<f64>>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(rng: &mut Rng) -> Perlin {
+        let gradients = (0..POINT_COUNT)
+            .map(|_| Vec3::random_unit_vector(rng))
+            .collect();
+
+        Perlin {
+            gradients,
+            perm_x: permutation(rng),
+            perm_y: permutation(rng),
+            perm_z: permutation(rng),
+        }
+    }
+
+    /// Get the noise at a point, roughly in [-1, 1].  It changes about once per unit of distance.
+    pub fn noise(&self, p: &Point3<f64>) -> f64 {
+        let (fx, fy, fz) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (u, v, w) = (p.x - fx, p.y - fy, p.z - fz);
+        let (i, j, k) = (fx as i64, fy as i64, fz as i64);
+
+        let mut sum = 0.0;
+
+        // Blend the gradients at the eight corners of the surrounding cell, with a smoothstep so
+        // the noise has no visible creases at cell boundaries.
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let gradient = self.gradients[self.perm_x[wrap(i + di)]
+                        ^ self.perm_y[wrap(j + dj)]
+                        ^ self.perm_z[wrap(k + dk)]];
+
+                    let offset = Vec3 {
+                        x: u - di as f64,
+                        y: v - dj as f64,
+                        z: w - dk as f64,
+                    };
+
+                    let weight = |t: f64, d: i64| {
+                        let s = t * t * (3.0 - 2.0 * t);
+                        if d == 1 {
+                            s
+                        } else {
+                            1.0 - s
+                        }
+                    };
+
+                    sum += weight(u, di) * weight(v, dj) * weight(w, dk) * gradient.dot(&offset);
+                }
+            }
+        }
+
+        sum
+    }
+
+    /// Sum the absolute value of `octaves` layers of noise, each twice as detailed and half as
+    /// strong as the last.  Always positive, with sharp creases where the noise crosses zero.
+    pub fn turbulence(&self, p: &Point3<f64>, octaves: u32) -> f64 {
+        self.octaves(p, octaves, f64::abs)
+    }
+
+    /// Fractional Brownian motion: like `turbulence`, but keeping the sign of each layer, which
+    /// gives soft, billowy shapes centered on zero.
+    pub fn fbm(&self, p: &Point3<f64>, octaves: u32) -> f64 {
+        self.octaves(p, octaves, |n| n)
+    }
+
+    fn octaves(&self, p: &Point3<f64>, octaves: u32, f: impl Fn(f64) -> f64) -> f64 {
+        let mut sum = 0.0;
+        let mut p = *p;
+        let mut weight = 1.0;
+
+        for _ in 0..octaves {
+            sum += weight * f(self.noise(&p));
+            weight *= 0.5;
+            p *= 2.0;
+        }
+
+        sum
+    }
+}
+
+fn wrap(i: i64) -> usize {
+    i.rem_euclid(POINT_COUNT as i64) as usize
+}
+
+/// Shuffle 0..POINT_COUNT.
+fn permutation(rng: &mut Rng) -> Vec<usize> {
+    let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+
+    for i in (1..POINT_COUNT).rev() {
+        let target = (rng.next_u32() as usize) % (i + 1);
+        p.swap(i, target);
+    }
+
+    p
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn perlin_noise() {
+    use crate::random::DEFAULT_SEED;
+
+    let perlin = Perlin::new(&mut Rng::new(DEFAULT_SEED));
+    let at = |x, y, z| Point3 { x, y, z };
+
+    // The same seed gives the same noise.
+    assert_eq!(perlin, Perlin::new(&mut Rng::new(DEFAULT_SEED)));
+    assert_ne!(perlin, Perlin::new(&mut Rng::new(DEFAULT_SEED + 1)));
+
+    // Zero on the lattice, and continuous between lattice points.
+    assert_eq!(perlin.noise(&at(3.0, -2.0, 7.0)), 0.0);
+
+    let a = perlin.noise(&at(0.5, 0.5, 0.5));
+    let b = perlin.noise(&at(0.5001, 0.5, 0.5));
+    assert!((a - b).abs() < 0.001);
+
+    for i in 0..1000 {
+        let p = at(i as f64 * 0.37, i as f64 * -0.11, i as f64 * 0.05);
+
+        assert!(perlin.noise(&p).abs() <= 1.5);
+        assert!(perlin.turbulence(&p, 7) >= 0.0);
+    }
+}
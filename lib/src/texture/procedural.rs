@@ -0,0 +1,185 @@
+///////////////////////////
+//  PROCEDURAL TEXTURES  //
+///////////////////////////
+
+use crate::random::Rng;
+use crate::texture::perlin::Perlin;
+use crate::texture::Texture;
+use crate::vec::{Color, Point3};
+use num::Float;
+use std::f64::consts::PI;
+
+/// Layers of detail added up by turbulence and fBm.
+const OCTAVES: u32 = 7;
+
+fn to_f64<T: Float>(p: &Point3<T>) -> Point3<f64> {
+    Point3 {
+        x: p.x.to_f64().unwrap(),
+        y: p.y.to_f64().unwrap(),
+        z: p.z.to_f64().unwrap(),
+    }
+}
+
+fn mix<T: Float>(a: Color<T>, b: Color<T>, t: f64) -> Color<T> {
+    let t = T::from(t.clamp(0.0, 1.0)).unwrap();
+
+    a * (T::one() - t) + b * t
+}
+
+//////////////
+//  MARBLE  //
+//////////////
+
+/// Bands of `vein` color through `base`, stacked along the z axis and warped by turbulence.
+pub struct Marble<T: Float> {
+    perlin: Perlin,
+    /// How closely the bands are packed.  There are about `scale / 2π` per unit of distance.
+    pub scale: T,
+    pub base: Color<T>,
+    pub vein: Color<T>,
+}
+
+impl<T: Float> Marble<T> {
+    pub fn new(scale: T, base: Color<T>, vein: Color<T>, rng: &mut Rng) -> Marble<T> {
+        Marble {
+            perlin: Perlin::new(rng),
+            scale,
+            base,
+            vein,
+        }
+    }
+}
+
+impl<T: Float + Send + Sync> Texture<T> for Marble<T> {
+    fn value(&self, _u: T, _v: T, p: &Point3<T>) -> Color<T> {
+        let p = to_f64(p);
+        let bands = p.z * self.scale.to_f64().unwrap();
+        let t = 0.5 * (1.0 + (bands + 10.0 * self.perlin.turbulence(&p, OCTAVES)).sin());
+
+        mix(self.vein, self.base, t)
+    }
+}
+
+////////////
+//  WOOD  //
+////////////
+
+/// Growth rings around the y axis, alternating between `light` and `dark`, made irregular with a
+/// little noise.
+pub struct Wood<T: Float> {
+    perlin: Perlin,
+    /// Rings per unit of distance from the axis.
+    pub scale: T,
+    pub light: Color<T>,
+    pub dark: Color<T>,
+}
+
+impl<T: Float> Wood<T> {
+    pub fn new(scale: T, light: Color<T>, dark: Color<T>, rng: &mut Rng) -> Wood<T> {
+        Wood {
+            perlin: Perlin::new(rng),
+            scale,
+            light,
+            dark,
+        }
+    }
+}
+
+impl<T: Float + Send + Sync> Texture<T> for Wood<T> {
+    fn value(&self, _u: T, _v: T, p: &Point3<T>) -> Color<T> {
+        let p = to_f64(p);
+        let scale = self.scale.to_f64().unwrap();
+
+        // Stretch the noise along the grain so the rings wobble slowly.
+        let grain = Point3 {
+            x: p.x * scale,
+            y: p.y * scale * 0.25,
+            z: p.z * scale,
+        };
+        let rings = (p.x * p.x + p.z * p.z).sqrt() * scale + 0.5 * self.perlin.fbm(&grain, 3);
+
+        // Sharp dark edges with wide light bands between them.
+        let t = (0.5 + 0.5 * (2.0 * PI * rings).sin()).powi(3);
+
+        mix(self.light, self.dark, t)
+    }
+}
+
+//////////////
+//  CLOUDS  //
+//////////////
+
+/// Soft, billowy patches of `cloud` over `sky`, from fBm noise.
+pub struct Clouds<T: Float> {
+    perlin: Perlin,
+    /// Patches per unit of distance, roughly.
+    pub scale: T,
+    /// Roughly how much of the surface is covered, from 0 to 1.
+    pub coverage: T,
+    pub sky: Color<T>,
+    pub cloud: Color<T>,
+}
+
+impl<T: Float> Clouds<T> {
+    pub fn new(scale: T, coverage: T, sky: Color<T>, cloud: Color<T>, rng: &mut Rng) -> Clouds<T> {
+        Clouds {
+            perlin: Perlin::new(rng),
+            scale,
+            coverage,
+            sky,
+            cloud,
+        }
+    }
+}
+
+impl<T: Float + Send + Sync> Texture<T> for Clouds<T> {
+    fn value(&self, _u: T, _v: T, p: &Point3<T>) -> Color<T> {
+        let p = to_f64(p) * self.scale.to_f64().unwrap();
+        let density = self.perlin.fbm(&p, OCTAVES);
+
+        // fBm is centered on zero, so shifting it moves the cloud edges in or out.
+        let threshold = 0.5 - self.coverage.to_f64().unwrap();
+        let t = (density - threshold * 0.8) * 2.5;
+
+        mix(self.sky, self.cloud, t)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn procedural_textures_stay_between_their_colors() {
+    use crate::random::DEFAULT_SEED;
+
+    let black = Color::zero();
+    let white = Color::one();
+    let mut rng = Rng::new(DEFAULT_SEED);
+
+    let textures: Vec<Box<dyn Texture<f64>>> = vec![
+        Box::new(Marble::new(4.0, white, black, &mut rng)),
+        Box::new(Wood::new(4.0, white, black, &mut rng)),
+        Box::new(Clouds::new(2.0, 0.5, black, white, &mut rng)),
+    ];
+
+    for texture in textures.iter() {
+        let values: Vec<f64> = (0..1000)
+            .map(|i| {
+                let p = Point3 {
+                    x: (i % 10) as f64 * 0.13,
+                    y: (i / 100) as f64 * 0.17,
+                    z: ((i / 10) % 10) as f64 * 0.11,
+                };
+
+                texture.value(0.0, 0.0, &p).x
+            })
+            .collect();
+
+        assert!(values.iter().all(|v| (0.0..=1.0).contains(v)));
+
+        // Each texture actually varies, using both of its colors.
+        assert!(values.iter().any(|&v| v < 0.2));
+        assert!(values.iter().any(|&v| v > 0.8));
+    }
+}
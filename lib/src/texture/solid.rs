@@ -0,0 +1,18 @@
+///////////////////
+//  SOLID COLOR  //
+///////////////////
+
+use crate::texture::Texture;
+use crate::vec::{Color, Point3};
+use num::Float;
+
+/// The same color everywhere.
+pub struct SolidColor<T: Float> {
+    pub color: Color<T>,
+}
+
+impl<T: Float + Send + Sync> Texture<T> for SolidColor<T> {
+    fn value(&self, _u: T, _v: T, _p: &Point3<T>) -> Color<T> {
+        self.color
+    }
+}
@@ -0,0 +1,333 @@
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                           TRANSFORMS                                           //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+use crate::aabb::Aabb;
+use crate::ray::Ray;
+use crate::vec::{Point3, Vec3};
+use num::Float;
+use std::fmt::Debug;
+
+type Matrix<T> = [[T; 4]; 4];
+
+/// An affine transform: any combination of translation, rotation and scaling.  It keeps its own
+/// inverse, which is built up exactly alongside it instead of being solved for.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transform<T: Float> {
+    matrix: Matrix<T>,
+    inverse: Matrix<T>,
+}
+
+impl<T: Float + Debug> Transform<T> {
+    pub fn identity() -> Transform<T> {
+        Transform {
+            matrix: identity(),
+            inverse: identity(),
+        }
+    }
+
+    pub fn translate(offset: Vec3<T>) -> Transform<T> {
+        let mut matrix = identity();
+        let mut inverse = identity();
+
+        for axis in 0..3 {
+            matrix[axis][3] = offset[axis];
+            inverse[axis][3] = -offset[axis];
+        }
+
+        Transform { matrix, inverse }
+    }
+
+    /// Scale by a factor along each axis.  None of the factors may be zero.
+    pub fn scale(factors: Vec3<T>) -> Transform<T> {
+        let mut matrix = identity();
+        let mut inverse = identity();
+
+        for axis in 0..3 {
+            matrix[axis][axis] = factors[axis];
+            inverse[axis][axis] = T::one() / factors[axis];
+        }
+
+        Transform { matrix, inverse }
+    }
+
+    /// Rotate counterclockwise by `degrees` when looking down `axis` toward the origin.
+    pub fn rotate(axis: Vec3<T>, degrees: T) -> Transform<T> {
+        let a = axis.unit();
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let t = T::one() - cos;
+
+        let mut matrix = identity();
+        matrix[0][0] = t * a.x * a.x + cos;
+        matrix[0][1] = t * a.x * a.y - sin * a.z;
+        matrix[0][2] = t * a.x * a.z + sin * a.y;
+        matrix[1][0] = t * a.x * a.y + sin * a.z;
+        matrix[1][1] = t * a.y * a.y + cos;
+        matrix[1][2] = t * a.y * a.z - sin * a.x;
+        matrix[2][0] = t * a.x * a.z - sin * a.y;
+        matrix[2][1] = t * a.y * a.z + sin * a.x;
+        matrix[2][2] = t * a.z * a.z + cos;
+
+        // A rotation's inverse is its transpose.
+        Transform {
+            matrix,
+            inverse: transpose(&matrix),
+        }
+    }
+
+    pub fn rotate_x(degrees: T) -> Transform<T> {
+        Transform::rotate(axis(0), degrees)
+    }
+
+    pub fn rotate_y(degrees: T) -> Transform<T> {
+        Transform::rotate(axis(1), degrees)
+    }
+
+    pub fn rotate_z(degrees: T) -> Transform<T> {
+        Transform::rotate(axis(2), degrees)
+    }
+
+    /// Combine two transforms into one that applies this one first and then `next`.
+    pub fn then(&self, next: &Transform<T>) -> Transform<T> {
+        Transform {
+            matrix: multiply(&next.matrix, &self.matrix),
+            inverse: multiply(&self.inverse, &next.inverse),
+        }
+    }
+
+    pub fn inverse(&self) -> Transform<T> {
+        Transform {
+            matrix: self.inverse,
+            inverse: self.matrix,
+        }
+    }
+
+    /// Get the determinant of the linear part, which is how much the transform scales volumes.
+    /// It's negative when the transform mirrors things.
+    pub fn determinant(&self) -> T {
+        let m = &self.matrix;
+
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn point(&self, p: &Point3<T>) -> Point3<T> {
+        apply(&self.matrix, p, T::one())
+    }
+
+    /// Transform a direction, which ignores translation.
+    pub fn vector(&self, v: &Vec3<T>) -> Vec3<T> {
+        apply(&self.matrix, v, T::zero())
+    }
+
+    /// Transform a surface normal so it stays perpendicular to the transformed surface.  The result
+    /// isn't a unit vector.
+    pub fn normal(&self, n: &Vec3<T>) -> Vec3<T> {
+        apply(&transpose(&self.inverse), n, T::zero())
+    }
+
+    /// Transform a ray.  Its direction isn't normalized, so distances along it are unchanged.
+    pub fn ray(&self, r: &Ray<T>) -> Ray<T> {
+        Ray {
+            origin: self.point(&r.origin),
+            direction: self.vector(&r.direction),
+            time: r.time,
+        }
+    }
+
+    /// Get the box enclosing a transformed box, which is usually larger than the box itself.
+    pub fn bounding_box(&self, bbox: &Aabb<T>) -> Aabb<T> {
+        let corner = |i: usize| Point3 {
+            x: if i & 1 == 0 { bbox.min.x } else { bbox.max.x },
+            y: if i & 2 == 0 { bbox.min.y } else { bbox.max.y },
+            z: if i & 4 == 0 { bbox.min.z } else { bbox.max.z },
+        };
+
+        let first = self.point(&corner(0));
+
+        (1..8).fold(Aabb::new(first, first), |b, i| {
+            let p = self.point(&corner(i));
+            b.surrounding(&Aabb::new(p, p))
+        })
+    }
+}
+
+impl<T: Float + Debug> Default for Transform<T> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// A placement broken into scaling, then rotation about x, y and z in turn, then translation.
+/// Unlike a matrix, two poses can be blended into a sensible pose partway between them, which is
+/// how instances are animated.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Pose<T: Float> {
+    pub scale: Vec3<T>,
+    /// Rotation about each axis, in degrees.
+    pub rotate: Vec3<T>,
+    pub translate: Vec3<T>,
+}
+
+impl<T: Float + Debug> Pose<T> {
+    pub fn transform(&self) -> Transform<T> {
+        Transform::scale(self.scale)
+            .then(&Transform::rotate_x(self.rotate.x))
+            .then(&Transform::rotate_y(self.rotate.y))
+            .then(&Transform::rotate_z(self.rotate.z))
+            .then(&Transform::translate(self.translate))
+    }
+
+    /// Get the pose a fraction `t` of the way from this one to `end`.
+    pub fn lerp(&self, end: &Pose<T>, t: T) -> Pose<T> {
+        Pose {
+            scale: self.scale + (end.scale - self.scale) * t,
+            rotate: self.rotate + (end.rotate - self.rotate) * t,
+            translate: self.translate + (end.translate - self.translate) * t,
+        }
+    }
+}
+
+impl<T: Float + Debug> Default for Pose<T> {
+    fn default() -> Self {
+        Pose {
+            scale: Vec3::one(),
+            rotate: Vec3::zero(),
+            translate: Vec3::zero(),
+        }
+    }
+}
+
+fn identity<T: Float>() -> Matrix<T> {
+    let mut m = [[T::zero(); 4]; 4];
+
+    for (i, row) in m.iter_mut().enumerate() {
+        row[i] = T::one();
+    }
+
+    m
+}
+
+fn axis<T: Float>(index: usize) -> Vec3<T> {
+    let unit = |i| if i == index { T::one() } else { T::zero() };
+
+    Vec3 {
+        x: unit(0),
+        y: unit(1),
+        z: unit(2),
+    }
+}
+
+fn transpose<T: Float>(m: &Matrix<T>) -> Matrix<T> {
+    let mut t = [[T::zero(); 4]; 4];
+
+    for (i, row) in m.iter().enumerate() {
+        for (j, value) in row.iter().enumerate() {
+            t[j][i] = *value;
+        }
+    }
+
+    t
+}
+
+fn multiply<T: Float>(a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T> {
+    let mut m = [[T::zero(); 4]; 4];
+
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..4).fold(T::zero(), |sum, k| sum + a[i][k] * b[k][j]);
+        }
+    }
+
+    m
+}
+
+/// Multiply a matrix by (v, w).  The bottom row of an affine matrix is (0, 0, 0, 1), so it's
+/// skipped.
+fn apply<T: Float>(m: &Matrix<T>, v: &Vec3<T>, w: T) -> Vec3<T> {
+    let row = |r: &[T; 4]| r[0] * v.x + r[1] * v.y + r[2] * v.z + r[3] * w;
+
+    Vec3 {
+        x: row(&m[0]),
+        y: row(&m[1]),
+        z: row(&m[2]),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//                                             TESTS                                              //
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn transforms_compose_and_invert() {
+    let p = Point3 {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    };
+    let close = |a: Vec3<f64>, b: Vec3<f64>| {
+        assert!((a - b).length() < 1e-9, "{:?} != {:?}", a, b);
+    };
+
+    // A quarter turn about y takes +x to -z.
+    close(
+        Transform::rotate_y(90.0).vector(&axis(0)),
+        Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        },
+    );
+
+    let t = Transform::scale(Vec3 {
+        x: 2.0,
+        y: 2.0,
+        z: 2.0,
+    })
+    .then(&Transform::rotate_z(90.0))
+    .then(&Transform::translate(Vec3 {
+        x: 10.0,
+        y: 0.0,
+        z: 0.0,
+    }));
+
+    // Scaled to (2, 4, 6), turned to (-4, 2, 6) and moved to (6, 2, 6).
+    close(
+        t.point(&p),
+        Point3 {
+            x: 6.0,
+            y: 2.0,
+            z: 6.0,
+        },
+    );
+    close(t.inverse().point(&t.point(&p)), p);
+
+    // Directions ignore translation.
+    close(
+        t.vector(&p),
+        Vec3 {
+            x: -4.0,
+            y: 2.0,
+            z: 6.0,
+        },
+    );
+
+    // Normals stay perpendicular to surfaces under uneven scaling.
+    let squash = Transform::scale(Vec3 {
+        x: 1.0,
+        y: 4.0,
+        z: 1.0,
+    });
+    let tangent = Vec3 {
+        x: 1.0,
+        y: -1.0,
+        z: 0.0,
+    };
+    let normal = Vec3 {
+        x: 1.0,
+        y: 1.0,
+        z: 0.0,
+    };
+    assert!(squash.vector(&tangent).dot(&squash.normal(&normal)).abs() < 1e-9);
+}
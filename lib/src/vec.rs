@@ -1,8 +1,8 @@
-use crate::random;
+use crate::random::Rng;
 use fmt::Display;
 use num::traits::{Float, Num};
 use std::fmt;
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, Mul, MulAssign, Neg, Sub, SubAssign};
 
 /// A vector with three components.
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -36,27 +36,27 @@ impl<T: Float> Vec3<T> {
 
     /// Create a vector randomly seeded with values in the range [0..1)
     #[allow(dead_code)]
-    pub fn random() -> Vec3<T> {
+    pub fn random(rng: &mut Rng) -> Vec3<T> {
         Vec3 {
-            x: random::random_float(),
-            y: random::random_float(),
-            z: random::random_float(),
+            x: rng.random_float(),
+            y: rng.random_float(),
+            z: rng.random_float(),
         }
     }
 
     /// Create a vector randomly seeded with values in the given range.
-    pub fn random_range(min: T, max: T) -> Vec3<T> {
+    pub fn random_range(rng: &mut Rng, min: T, max: T) -> Vec3<T> {
         Vec3 {
-            x: random::random_float_in_range(min, max),
-            y: random::random_float_in_range(min, max),
-            z: random::random_float_in_range(min, max),
+            x: rng.random_float_in_range(min, max),
+            y: rng.random_float_in_range(min, max),
+            z: rng.random_float_in_range(min, max),
         }
     }
 
     /// Create a vector randomly seeded with a point inside the unit sphere.
-    pub fn random_in_unit_sphere() -> Vec3<T> {
+    pub fn random_in_unit_sphere(rng: &mut Rng) -> Vec3<T> {
         loop {
-            let p = Vec3::random_range(-T::one(), T::one());
+            let p = Vec3::random_range(rng, -T::one(), T::one());
 
             if p.length_squared() >= T::one() {
                 continue;
@@ -67,14 +67,14 @@ impl<T: Float> Vec3<T> {
     }
 
     /// Create a unit vector pointing in a random direction.
-    pub fn random_unit_vector() -> Vec3<T> {
-        Vec3::<T>::random_in_unit_sphere().unit()
+    pub fn random_unit_vector(rng: &mut Rng) -> Vec3<T> {
+        Vec3::<T>::random_in_unit_sphere(rng).unit()
     }
 
     /// Create a vector randomly seeded with a point inside the unit hemisphere occupied by the
     /// given normal.
-    pub fn random_in_hemisphere(normal: &Vec3<T>) -> Vec3<T> {
-        let in_unit_sphere = Vec3::random_in_unit_sphere();
+    pub fn random_in_hemisphere(rng: &mut Rng, normal: &Vec3<T>) -> Vec3<T> {
+        let in_unit_sphere = Vec3::random_in_unit_sphere(rng);
         if in_unit_sphere.dot(normal) > T::zero() {
             in_unit_sphere
         } else {
@@ -84,11 +84,11 @@ impl<T: Float> Vec3<T> {
 
     /// Create a vector random seeded within a unit disk.  To be used as an origin point for
     /// casting rays from a virtual film plane.
-    pub fn random_in_unit_disk() -> Vec3<T> {
+    pub fn random_in_unit_disk(rng: &mut Rng) -> Vec3<T> {
         loop {
             let p = Vec3 {
-                x: random::random_float_in_range(-T::one(), T::one()),
-                y: random::random_float_in_range(-T::one(), T::one()),
+                x: rng.random_float_in_range(-T::one(), T::one()),
+                y: rng.random_float_in_range(-T::one(), T::one()),
                 z: T::zero(),
             };
 
@@ -312,7 +312,7 @@ impl<T: Num + Copy> Mul<T> for Vec3<T> {
     }
 }
 
-impl<'a, T: Num + Copy> Mul<T> for &'a Vec3<T> {
+impl<T: Num + Copy> Mul<T> for &Vec3<T> {
     type Output = Vec3<T>;
 
     #[inline]
@@ -413,6 +413,25 @@ impl<T: Num + Copy + DivAssign> DivAssign<T> for Vec3<T> {
     }
 }
 
+/////////////
+//  INDEX  //
+/////////////
+
+// Vec3[0] == Vec3.x, useful when looping over axes.
+impl<T: Num + Copy> Index<usize> for Vec3<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, axis: usize) -> &T {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vec3 axis index out of range: {}", axis),
+        }
+    }
+}
+
 #[allow(dead_code)]
 impl<T: Num + Copy> Vec3<T> {
     /// Get the magnitude squared of this vector.
@@ -452,6 +471,12 @@ impl<T: Float> Vec3<T> {
         *self / (self.length())
     }
 
+    /// Get the largest of the three components.
+    #[inline]
+    pub fn max_component(&self) -> T {
+        self.x.max(self.y).max(self.z)
+    }
+
     /// Normalize this vector; reduce it to length 1.
     #[inline]
     pub fn self_unit(&mut self) -> &Vec3<T> {
@@ -461,6 +486,28 @@ impl<T: Float> Vec3<T> {
         self.z = self.z / length;
         self
     }
+
+    /// Turn a direction given relative to this unit vector, with z along it, into an absolute
+    /// direction.  Useful for sampling directions around a normal or toward a light.
+    pub fn from_local(&self, local: &Vec3<T>) -> Vec3<T> {
+        let a = if self.x.abs() > T::from(0.9).unwrap() {
+            Vec3 {
+                x: T::zero(),
+                y: T::one(),
+                z: T::zero(),
+            }
+        } else {
+            Vec3 {
+                x: T::one(),
+                y: T::zero(),
+                z: T::zero(),
+            }
+        };
+        let v = self.cross(&a).unit();
+        let u = self.cross(&v);
+
+        u * local.x + v * local.y + *self * local.z
+    }
 }
 
 impl<T: Display + Num + Copy> Display for Vec3<T> {
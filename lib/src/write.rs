@@ -9,4 +9,6 @@ pub struct FinalImage {
     pub height: u32,
     pub samples_per_pixel: i32,
     pub total_rays: u64,
+    /// Paths ended early by Russian roulette.
+    pub terminated_paths: u64,
 }
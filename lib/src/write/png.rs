@@ -3,6 +3,7 @@ use crate::vec::Color;
 use image;
 use num::clamp;
 use std::env;
+use std::path::Path;
 use std::time;
 
 /// Write an image file to a temp directory.  Image size and contents are passed in a FinalImage
@@ -21,6 +22,15 @@ pub fn write(image_data: FinalImage) {
     let temp_dir = env::temp_dir();
     let temp_file = temp_dir.join(&filename);
 
+    match write_to(&image_data, &temp_file) {
+        Ok(_) => println!("Wrote {}", filename),
+        Err(err) => println!("Error writing {}", err),
+    }
+}
+
+/// Write an image file to the given path.  The format is chosen from the file extension, so
+/// besides PNG this can write anything the image crate supports, such as JPEG or PPM.
+pub fn write_to(image_data: &FinalImage, path: &Path) -> image::ImageResult<()> {
     let mut buf = image::ImageBuffer::new(image_data.width, image_data.height);
 
     for (i, pixel) in image_data.pixels.iter().enumerate() {
@@ -30,10 +40,7 @@ pub fn write(image_data: FinalImage) {
         buf.put_pixel(x, y, image::Rgb([color.x, color.y, color.z]));
     }
 
-    match buf.save(temp_file) {
-        Ok(_) => println!("Wrote {}", filename),
-        Err(err) => println!("Error writing {}", err),
-    }
+    buf.save(path)
 }
 
 pub fn get_color_u8(pixel_color: &Color<f64>, samples_per_pixel: i32) -> Color<u8> {
@@ -169,6 +169,9 @@ Time per ray      = ${((this.timer.duration / total_rays) * 1000).toFixed(
 Ray rate          = ${(total_rays / this.timer.duration / 1000).toFixed(
             4
         )} rays/microsecond
+Roulette-ended    = ${Number(renderResult.terminated_paths).toLocaleString(
+            "en-US"
+        )} paths
 Image width       = ${renderResult.width}
 Image height      = ${renderResult.height}
 Samples per pixel = ${renderResult.samples_per_pixel}`;
@@ -1,6 +1,5 @@
 mod utils;
 
-use rtw_lib;
 use wasm_bindgen::prelude::*;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
@@ -9,18 +8,14 @@ use wasm_bindgen::prelude::*;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-use num::traits::Float;
-use rtw_lib::random::random_float;
-
-use rtw_lib::camera::Camera;
-use rtw_lib::hit::HitRecord;
-use rtw_lib::material::Lambertian;
+use rtw_lib::environment::Environment;
+use rtw_lib::hit::HittableList;
+use rtw_lib::integrator::IntegratorKind;
+use rtw_lib::random::DEFAULT_SEED;
+use rtw_lib::render::{RenderSettings, Renderer};
 use rtw_lib::scenes as Scenes;
-use rtw_lib::vec::{Color, Point3, Vec3};
 use rtw_lib::write::png::get_color_u8;
 
-use std::rc::Rc;
-
 #[wasm_bindgen]
 pub struct WasmFinalImage {
     pixels: Vec<u8>,
@@ -28,13 +23,14 @@ pub struct WasmFinalImage {
     pub height: u32,
     pub samples_per_pixel: i32,
     pub total_rays: u64,
+    pub terminated_paths: u64,
 }
 
 #[wasm_bindgen]
 impl WasmFinalImage {
     #[wasm_bindgen(getter)]
     pub fn pixels(&self) -> js_sys::Uint8ClampedArray {
-        return js_sys::Uint8ClampedArray::from(&self.pixels[..]);
+        js_sys::Uint8ClampedArray::from(&self.pixels[..])
     }
 }
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -44,6 +40,9 @@ impl WasmFinalImage {
 /// Example render.
 #[wasm_bindgen]
 pub fn render() -> WasmFinalImage {
+    // Show Rust panics in the browser console.
+    utils::set_panic_hook();
+
     // Configuration
 
     // let aspect_ratio = 3.0 / 2.0;
@@ -53,113 +52,48 @@ pub fn render() -> WasmFinalImage {
     // let max_depth = 10;
 
     let aspect_ratio = 3.0 / 2.0;
-    let width = 500.0;
+    let width: f64 = 500.0;
     let height = (width / aspect_ratio).floor();
 
     // let samples_per_pixel: i32 = 100;
     // let max_depth = 25;
 
-    let samples_per_pixel: i32 = 10;
-    let max_depth = 5;
+    // WebAssembly can't spawn threads, so render on this one.
+    let settings = RenderSettings {
+        width: width as u32,
+        height: height as u32,
+        samples_per_pixel: 10,
+        max_depth: 50,
+        seed: DEFAULT_SEED,
+        threads: 1,
+        integrator: IntegratorKind::Path,
+    };
 
     // World
 
     // let world = Scenes::random_scene::scene();
     // let world = Scenes::ten_spheres::scene();
     let world = Scenes::three_sphere_scene::scene();
+    let lights = HittableList::new(Vec::new());
 
     // Camera
 
-    let lookfrom = Point3 {
-        x: 0.0,
-        y: 1.8,
-        z: 1.4,
-    };
-    let lookat = Point3 {
-        x: 0.0,
-        y: 0.0,
-        z: -3.0,
-    };
-    let vup = Vec3 {
-        x: 0.0,
-        y: 1.0,
-        z: 0.0,
-    };
-    let dist_to_focus = 10.0;
-    let aperture = 0.0;
-    let cam = Camera::new(
-        lookfrom,
-        lookat,
-        vup,
-        45.0,
-        aspect_ratio,
-        aperture,
-        dist_to_focus,
-    );
-
-    // Render
-
-    let mut pixels = vec![
-        Vec3 {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
-        };
-        (width * height) as usize
-    ];
-
-    // Default material to clone into each HitRecord
-    let default_material = Rc::new(Lambertian {
-        albedo: Color {
-            x: 122.0 / 255.0,
-            y: 175.0 / 255.0,
-            z: 238.0 / 255.0,
-        },
-    });
-
-    let mut total_rays: u64 = 0;
-
-    let mut i: usize = 0;
-    for y in (0..(height as i32)).rev() {
-        for x in 0..(width as i32) {
-            let p = &mut pixels[i];
-
-            for _ in 0..samples_per_pixel {
-                // don't use RNG if there's only one sample per pixel
-                let u_rand = if samples_per_pixel > 1 {
-                    random_float()
-                } else {
-                    1.0
-                };
-
-                let v_rand = if samples_per_pixel > 1 {
-                    random_float()
-                } else {
-                    1.0
-                };
-
-                let u = (u_rand + x as f64) / (width - 1.0);
-                let v = (v_rand + y as f64) / (height - 1.0);
+    let cam = Scenes::three_sphere_scene::camera().build(aspect_ratio);
 
-                let ray = cam.get_ray(u, v);
+    // Environment
 
-                let mut rec = HitRecord::new(default_material.clone());
+    let environment = Environment::sky();
 
-                *p += ray.color(&mut rec, &world, max_depth);
-
-                total_rays += rec.ray_count;
-            }
+    // Render
 
-            i += 1;
-        }
-    }
+    let image = Renderer::new(&world, &lights, &environment, &cam, settings).render();
 
-    let mut raw_pixels = vec![0u8; (4.0 * width * height) as usize];
+    let mut raw_pixels = vec![0u8; 4 * image.pixels.len()];
 
     let mut i: usize = 0;
-    for p in pixels {
-        let color = get_color_u8(&p, samples_per_pixel);
-        raw_pixels[i + 0] = color.x;
+    for p in image.pixels.iter() {
+        let color = get_color_u8(p, image.samples_per_pixel);
+        raw_pixels[i] = color.x;
         raw_pixels[i + 1] = color.y;
         raw_pixels[i + 2] = color.z;
         raw_pixels[i + 3] = 255;
@@ -168,9 +102,10 @@ pub fn render() -> WasmFinalImage {
 
     WasmFinalImage {
         pixels: raw_pixels,
-        total_rays,
-        width: width as u32,
-        height: height as u32,
-        samples_per_pixel,
+        total_rays: image.total_rays,
+        terminated_paths: image.terminated_paths,
+        width: image.width,
+        height: image.height,
+        samples_per_pixel: image.samples_per_pixel,
     }
 }
@@ -34,6 +34,7 @@ addEventListener("message", async (e) => {
                     renderResult: {
                         pixels: renderResult.pixels,
                         total_rays: renderResult.total_rays,
+                        terminated_paths: renderResult.terminated_paths,
                         width: renderResult.width,
                         height: renderResult.height,
                         samples_per_pixel: renderResult.samples_per_pixel,